@@ -1,5 +1,7 @@
 use std::collections::HashMap;
+use std::fmt;
 
+use crate::source::Span;
 use crate::value::Value;
 
 #[derive(Clone,Debug)] 
@@ -8,42 +10,74 @@ pub enum Program {
 }
 
 #[derive(Clone,Debug)] 
-pub enum Statement {
-    Import{path: String},
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(Clone,Debug)] 
+pub enum StatementKind {
+    Import{path: String, names: ImportedNames},
     Expression{expression: Expression},
     Assignment{lhs: Expression, rhs: Expression},
+    Declaration{name: String, annotation: TypeAnnotation, rhs: Expression},
     OperatorAssignment{name: String, 
                        operator: Operator, 
                        rhs: Expression},
     
     If{params: IfBranch},
     
-    While{condition: Expression, statements: Vec<Statement>},
+    While{label: Option<String>, condition: Expression, statements: Vec<Statement>},
     
     For{params: ForLoop},
 
+    Match{subject: Expression, arms: Vec<MatchArm>},
+
+    Throw{value: Expression},
+    Try{statements: Vec<Statement>, 
+        catch: Option<CatchClause>, 
+        finally: Option<Vec<Statement>>},
+
     FunctionDefinition{name: String, 
                        arguments: Vec<String>, 
-                       statements: Vec<Statement>,
-                       return_expression: Option<Expression> },
+                       signature: Signature,
+                       statements: Vec<Statement> },
+    EnumDefinition{name: String, variants: Vec<EnumVariant>},
+    // A definition marked `pub` at the top of a file, which imports bring in
+    Public{statement: Box<Statement>},
+    Return{expression: Option<Expression>},
+    Break{label: Option<String>},
+    Continue{label: Option<String>},
 }
 
 #[derive(Clone,Debug)] 
-pub enum Expression {
+pub struct Expression {
+    pub kind: ExpressionKind,
+    pub span: Span,
+}
+
+#[derive(Clone,Debug)] 
+pub enum ExpressionKind {
     // BEGIN TYPES
-    Int{v: i32},
+    // Checked to fit in an int before the program runs, as `-` is only
+    // folded into the literal after it is parsed
+    Int{v: i64},
     String{s: String},
     Boolean{b: bool},
     Float{f: f64},
     Character{c: char},
     List{items: Vec<ListItem>},
+    Map{entries: Vec<(Expression, Expression)>},
+    Set{items: Vec<Expression>},
     // END TYPES
     ClassDef{params: Box<UserClass>},
     Identifier{name: String},
-    Call{function: String, arguments: Vec<Expression>},
+    Call{function: Box<Expression>, arguments: Vec<Expression>},
+    Lambda{arguments: Vec<String>, body: Box<Expression>},
 
     Operation{lhs: Box<Expression>, rhs: Box<Expression>, operator: Operator},
     Prefix{name: String, operator:Operator, rhs: Box<Expression>},
+    Unary{operator: Operator, operand: Box<Expression>},
 
     Index{name: String, idx_exp: Box<Expression>},
 
@@ -53,18 +87,79 @@ pub enum Expression {
         
     FieldAccess{name: String, field: String},
 
-    ObjectCreation{class_name: String, arguments: Vec<Expression>},
+    // `module` is set for `new m.Point(...)`, with a class from a module
+    ObjectCreation{module: Option<String>, class_name: String, 
+                   arguments: Vec<Expression>},
 
     MethodCall{name: String, method: String, arguments: Vec<Expression>}
 }
 
+impl Statement {
+    pub fn new(kind: StatementKind, span: Span) -> Self {
+        Statement{kind, span}
+    }
+}
+
+impl Expression {
+    pub fn new(kind: ExpressionKind, span: Span) -> Self {
+        Expression{kind, span}
+    }
+
+    /// Applies `operator` to `operand`. A negated integer literal becomes a
+    /// literal itself, so that `-2147483648` fits in an int.
+    pub fn unary(operator: Operator, operand: Expression, span: Span) -> Self {
+        match (&operator, &operand.kind) {
+            (Operator::Minus, ExpressionKind::Int { v }) 
+                => Expression::new(ExpressionKind::Int{v: -v}, span),
+            _ => Expression::new(ExpressionKind::Unary{operator, 
+                                                       operand: Box::new(operand)}, 
+                                 span),
+        }
+    }
+}
+
 #[derive(Clone,Debug)] 
 pub struct ForLoop {
+    pub label: Option<String>,
     pub loop_var: String,
     pub iterate_expression: Expression,
     pub statements: Vec<Statement>,
 }
 
+/// What an import brings into the importing file's scope.
+#[derive(Clone,Debug)] 
+pub enum ImportedNames {
+    // `import "lib.txt";` brings in every name the file marks `pub`
+    All,
+    // `import "lib.txt" as m;` binds the file's names to the module `m`
+    Module{name: String},
+    // `from "lib.txt" import a, b;`
+    Listed{names: Vec<String>},
+}
+
+/// One of an enum's variants, with the names of the values it carries.
+#[derive(Clone,Debug)] 
+pub struct EnumVariant {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+/// A type written after `:` or `->`, like `int` or `list[str]`.
+#[derive(Clone,Debug)] 
+pub struct TypeAnnotation {
+    pub name: String,
+    pub element: Option<Box<TypeAnnotation>>,
+    pub span: Span,
+}
+
+/// The annotated types of a function's parameters, in order, and of what it
+/// returns. Unannotated parts are `None`.
+#[derive(Clone,Debug)] 
+pub struct Signature {
+    pub parameter_types: Vec<Option<TypeAnnotation>>,
+    pub return_type: Option<TypeAnnotation>,
+}
+
 #[derive(Clone,Debug)] 
 pub struct IfBranch {
     pub condition: Expression,
@@ -73,6 +168,40 @@ pub struct IfBranch {
     pub elif_data: (Vec<Expression>, Vec<Vec<Statement>>),
 }
 
+/// A `match` arm: its body runs for the first arm whose pattern fits the
+/// subject and whose guard, if any, is true.
+#[derive(Clone,Debug)] 
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub statements: Vec<Statement>,
+}
+
+/// The `catch (name) { ... }` part of a `try`, run with the caught error
+/// bound to `name`.
+#[derive(Clone,Debug)] 
+pub struct CatchClause {
+    pub name: String,
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Clone,Debug)] 
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub span: Span,
+}
+
+#[derive(Clone,Debug)] 
+pub enum PatternKind {
+    Wildcard,
+    Literal{value: Box<Value>},
+    Binding{name: String},
+    // `rest` is the name after a trailing `..`, bound to the remaining items
+    List{items: Vec<Pattern>, rest: Option<String>},
+    Object{class_name: String, fields: Vec<(String, Pattern)>},
+    Variant{enum_name: String, tag: String, items: Vec<Pattern>},
+}
+
 #[derive(Clone,Debug)] 
 pub struct ListItem {
     pub expression: Expression,
@@ -92,21 +221,23 @@ pub struct UserClass {
 #[derive(Clone,Debug)]
 pub struct ClassField {
     pub is_private: bool,
-    pub value: Value
+    pub value: Value,
+    pub annotation: Option<TypeAnnotation>,
 }
 
 #[derive(Clone,Debug)]
 pub struct ClassInitDef {
     pub name: Option<String>,
     pub arguments: Option<Vec<String>>, 
+    pub parameter_types: Vec<Option<TypeAnnotation>>,
     pub statements: Option<Vec<Statement>>,
 }
 
 #[derive(Clone,Debug)]
 pub struct ClassMethod {
     pub arguments: Vec<String>,
+    pub signature: Signature,
     pub statements: Vec<Statement>,
-    pub return_exp: Option<Expression>,
     pub is_private: bool
 }
 
@@ -116,8 +247,42 @@ pub enum Operator {
     Minus,
     Times,
     Divide,
+    Modulo,
+    Power,
+    FloorDivide,
     LessThan,
     GreaterThan,
+    LessEqual,
+    GreaterEqual,
     Equal,
-    NotEqual
+    NotEqual,
+    In,
+    And,
+    Or,
+    Not
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Operator::Plus => "+",
+            Operator::Minus => "-",
+            Operator::Times => "*",
+            Operator::Divide => "/",
+            Operator::Modulo => "%",
+            Operator::Power => "**",
+            Operator::FloorDivide => "~/",
+            Operator::LessThan => "<",
+            Operator::GreaterThan => ">",
+            Operator::LessEqual => "<=",
+            Operator::GreaterEqual => ">=",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::In => "in",
+            Operator::And => "&&",
+            Operator::Or => "||",
+            Operator::Not => "!",
+        };
+        write!(f, "{}", symbol)
+    }
 }
@@ -0,0 +1,417 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::collections::{ValueMap, ValueSet};
+use crate::error::BrainstormError;
+use crate::eval::Interpreter;
+use crate::value::{Builtin, Value};
+
+/// The native functions every program starts out with.
+pub const BUILTINS: &[(&str, Builtin)] = &[
+    ("println", println_),
+    ("print", print_),
+    ("range", range),
+    ("range_step", range_step),
+    ("map", map),
+    ("filter", filter),
+    ("reduce", reduce),
+    ("sort", sort),
+    ("sort_by", sort_by),
+    ("any", any),
+    ("all", all),
+    ("enumerate", enumerate),
+    ("zip", zip),
+    ("keys", keys),
+    ("values", values),
+    ("has", has),
+    ("remove", remove),
+    ("set", set),
+    ("union", union),
+    ("intersection", intersection),
+    ("difference", difference),
+];
+
+/// Adds the builtins to `env`.
+pub fn insert_builtins(env: &mut HashMap<String, Value>) {
+    for (name, f) in BUILTINS {
+        env.insert(name.to_string(), Value::Function{name: name.to_string(), f: *f});
+    }
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn println_(_: &mut Interpreter, args: Vec<Value>) -> Result<Value, BrainstormError> {
+    for arg in args {
+        print!("{}", arg);
+    }
+    println!();
+    Ok(Value::Null)
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn print_(_: &mut Interpreter, args: Vec<Value>) -> Result<Value, BrainstormError> {
+    for arg in args {
+        print!("{}", arg);
+    }
+    Ok(Value::Null)
+}
+
+pub fn range_step(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let mut vals = vec![];
+
+    let [start, end, step] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 3 arguments, got {}", args.len())))
+    };
+
+    let Value::Int{v: s} = start 
+        else { return Err(BrainstormError::type_error("Invalid Type"))};
+    
+    let Value::Int{v: e} = end 
+        else { return Err(BrainstormError::type_error("Invalid Type"))};
+
+    let Value::Int{v: st} = step 
+        else { return Err(BrainstormError::type_error("Invalid Type"))};
+
+    let Ok(st) = usize::try_from(*st) else {
+        return Err(BrainstormError::type_error("Step must be positive"))
+    };
+    if st == 0 {
+        return Err(BrainstormError::type_error("Step must be positive"))
+    }
+    
+    for x in (*s..*e).step_by(st) {
+        vals.push(Value::Int{v: x});
+    }
+
+    Ok(Value::List{e: vals})
+}
+
+pub fn range(interpreter: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    if args.len() != 2 {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    }
+
+    let mut vals = args;
+    vals.push(Value::Int{v: 1});
+    range_step(interpreter, vals)
+}
+
+/// `map(f, items)`: the results of calling `f` on each item.
+pub fn map(interpreter: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [f, items] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    };
+
+    let mut vals = vec![];
+    for item in items.items()? {
+        vals.push(interpreter.call(f, vec![item])?);
+    }
+
+    Ok(Value::List{e: vals})
+}
+
+/// `filter(f, items)`: the items for which `f` returns true.
+pub fn filter(interpreter: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [f, items] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    };
+
+    let mut vals = vec![];
+    for item in items.items()? {
+        if test(interpreter, "filter", Some(f), item.clone())? {
+            vals.push(item);
+        }
+    }
+
+    Ok(Value::List{e: vals})
+}
+
+/// `reduce(f, items, initial)`: folds the items into one value with
+/// `f(accumulator, item)`. Without `initial`, the first item is used.
+pub fn reduce(interpreter: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let (f, items, initial) = match args.as_slice() {
+        [f, items] => (f, items.items()?, None),
+        [f, items, initial] => (f, items.items()?, Some(initial.clone())),
+        _ => return Err(BrainstormError::arity_error(
+            format!("Expected 2 or 3 arguments, got {}", args.len())))
+    };
+
+    let mut items = items.into_iter();
+    let Some(mut accumulator) = initial.or_else(|| items.next()) else {
+        return Err(BrainstormError::type_error(
+            "Cannot reduce an empty list without an initial value"))
+    };
+
+    for item in items {
+        accumulator = interpreter.call(f, vec![accumulator, item])?;
+    }
+
+    Ok(accumulator)
+}
+
+/// `sort(items)`: the items in ascending order.
+pub fn sort(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [items] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 1 argument, got {}", args.len())))
+    };
+
+    let mut vals = items.items()?;
+    sort_values(&mut vals, |v| v)?;
+
+    Ok(Value::List{e: vals})
+}
+
+/// `sort_by(f, items)`: the items in ascending order of the key `f` 
+/// returns for them. Items with equal keys keep their order.
+pub fn sort_by(interpreter: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [f, items] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    };
+
+    let mut keyed = vec![];
+    for item in items.items()? {
+        keyed.push((interpreter.call(f, vec![item.clone()])?, item));
+    }
+    sort_values(&mut keyed, |(key, _)| key)?;
+
+    Ok(Value::List{e: keyed.into_iter().map(|(_, item)| item).collect()})
+}
+
+/// `any(items)` or `any(f, items)`: whether any item is true, or makes 
+/// `f` return true.
+pub fn any(interpreter: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let (f, items) = predicate_args(args)?;
+
+    for item in items {
+        if test(interpreter, "any", f.as_ref(), item)? {
+            return Ok(Value::Bool{b: true})
+        }
+    }
+
+    Ok(Value::Bool{b: false})
+}
+
+/// `all(items)` or `all(f, items)`: whether every item is true, or makes 
+/// `f` return true.
+pub fn all(interpreter: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let (f, items) = predicate_args(args)?;
+
+    for item in items {
+        if !test(interpreter, "all", f.as_ref(), item)? {
+            return Ok(Value::Bool{b: false})
+        }
+    }
+
+    Ok(Value::Bool{b: true})
+}
+
+/// `enumerate(items)`: `[index, item]` pairs, counting from 0.
+pub fn enumerate(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [items] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 1 argument, got {}", args.len())))
+    };
+
+    let mut vals = vec![];
+    for (idx, item) in items.items()?.into_iter().enumerate() {
+        let Ok(v) = i32::try_from(idx) else {
+            return Err(BrainstormError::arithmetic_error("Index overflow in enumerate"))
+        };
+        vals.push(Value::List{e: vec![Value::Int{v}, item]});
+    }
+
+    Ok(Value::List{e: vals})
+}
+
+/// `zip(a, b)`: `[a_item, b_item]` pairs, as many as the shorter input has.
+pub fn zip(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [lhs, rhs] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    };
+
+    let vals = lhs.items()?.into_iter()
+        .zip(rhs.items()?)
+        .map(|(l, r)| Value::List{e: vec![l, r]})
+        .collect();
+
+    Ok(Value::List{e: vals})
+}
+
+/// `keys(m)`: the keys of a map, in the order they were added.
+pub fn keys(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [map] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 1 argument, got {}", args.len())))
+    };
+
+    let m = expect_map(map)?;
+    Ok(Value::List{e: m.keys().cloned().collect()})
+}
+
+/// `values(m)`: the values of a map, in the order their keys were added.
+pub fn values(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [map] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 1 argument, got {}", args.len())))
+    };
+
+    let m = expect_map(map)?;
+    Ok(Value::List{e: m.values().cloned().collect()})
+}
+
+/// `has(m, key)`: whether the map has a value for `key`.
+pub fn has(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [map, key] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    };
+
+    let m = expect_map(map)?;
+    Ok(Value::Bool{b: m.contains_key(key)})
+}
+
+/// `remove(m, key)`: a copy of the map or set without `key`. Like every 
+/// other value, the one passed in is left as it was.
+pub fn remove(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let [collection, key] = args.as_slice() else {
+        return Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    };
+
+    if let Value::Set { e } = collection {
+        let mut e = e.clone();
+        e.remove(key);
+        return Ok(Value::Set{e})
+    }
+
+    let mut m = expect_map(collection)?.clone();
+    m.remove(key);
+    Ok(Value::Map{m})
+}
+
+/// `set()` or `set(items)`: a set of the distinct items, in the order they
+/// first appear.
+pub fn set(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let mut e = ValueSet::new();
+    match args.as_slice() {
+        [] => {},
+        [items] => {
+            for item in items.items()? {
+                e.insert(item)?;
+            }
+        },
+        _ => return Err(BrainstormError::arity_error(
+            format!("Expected 0 or 1 arguments, got {}", args.len())))
+    }
+
+    Ok(Value::Set{e})
+}
+
+/// `union(a, b)`: the items in either set.
+pub fn union(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let (lhs, rhs) = expect_sets(&args)?;
+    Ok(Value::Set{e: lhs.union(rhs)})
+}
+
+/// `intersection(a, b)`: the items in both sets.
+pub fn intersection(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let (lhs, rhs) = expect_sets(&args)?;
+    Ok(Value::Set{e: lhs.intersection(rhs)})
+}
+
+/// `difference(a, b)`: the items in `a` but not in `b`.
+pub fn difference(_: &mut Interpreter, args: Vec<Value>) 
+    -> Result<Value, BrainstormError> {
+    let (lhs, rhs) = expect_sets(&args)?;
+    Ok(Value::Set{e: lhs.difference(rhs)})
+}
+
+fn expect_sets(args: &[Value]) -> Result<(&ValueSet, &ValueSet), BrainstormError> {
+    match args {
+        [Value::Set { e: lhs }, Value::Set { e: rhs }] => Ok((lhs, rhs)),
+        [lhs, rhs] => Err(BrainstormError::type_error(
+            format!("Expected two Sets, got {} and {}", 
+                    lhs.type_name(), rhs.type_name()))),
+        _ => Err(BrainstormError::arity_error(
+            format!("Expected 2 arguments, got {}", args.len())))
+    }
+}
+
+fn expect_map(value: &Value) -> Result<&ValueMap, BrainstormError> {
+    match value {
+        Value::Map { m } => Ok(m),
+        v => Err(BrainstormError::type_error(
+            format!("Expected a Map, got {}", v.type_name())))
+    }
+}
+
+// Calls a predicate, which must answer with a Bool. Without a predicate the
+// item itself is the answer.
+fn test(interpreter: &mut Interpreter, builtin: &str, f: Option<&Value>, 
+        item: Value) -> Result<bool, BrainstormError> {
+    let answer = match f {
+        Some(f) => interpreter.call(f, vec![item])?,
+        None => item,
+    };
+
+    match answer {
+        Value::Bool { b } => Ok(b),
+        v => Err(BrainstormError::type_error(
+            format!("'{}' expects Bool values, got {}", builtin, v.type_name())))
+    }
+}
+
+fn predicate_args(args: Vec<Value>) 
+    -> Result<(Option<Value>, Vec<Value>), BrainstormError> {
+    match args.as_slice() {
+        [items] => Ok((None, items.items()?)),
+        [f, items] => Ok((Some(f.clone()), items.items()?)),
+        _ => Err(BrainstormError::arity_error(
+            format!("Expected 1 or 2 arguments, got {}", args.len())))
+    }
+}
+
+/// Sorts `vals` by `key`, which has to give values that can all be compared
+/// with each other, so that they have a total order.
+fn sort_values<T>(vals: &mut [T], key: impl Fn(&T) -> &Value) 
+    -> Result<(), BrainstormError> {
+    if let Some(first) = vals.first().map(&key) {
+        for val in vals.iter().map(&key) {
+            if first.compare(val).is_none() {
+                return Err(BrainstormError::type_error(
+                    format!("Cannot compare {} and {}", 
+                            first.type_name(), val.type_name())))
+            }
+            if matches!(val, Value::Float { f } if f.is_nan()) {
+                return Err(BrainstormError::type_error("Cannot compare NaN"))
+            }
+        }
+    }
+
+    vals.sort_by(|l, r| key(l).compare(key(r)).unwrap_or(Ordering::Equal));
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use crate::ast::{Expression, ExpressionKind, Program, Statement, StatementKind};
+use crate::error::BrainstormError;
+
+/// The functions and loops enclosing a statement.
+#[derive(Clone,Default)]
+struct Scope {
+    in_function: bool,
+    loops: Vec<Option<String>>,
+}
+
+impl Scope {
+    fn function() -> Self {
+        Scope{in_function: true, loops: vec![]}
+    }
+
+    fn enter_loop(&self, label: &Option<String>) -> Self {
+        let mut scope = self.clone();
+        scope.loops.push(label.clone());
+        scope
+    }
+
+    fn check_jump(&self, keyword: &str, label: &Option<String>) 
+        -> Result<(), BrainstormError> {
+        if self.loops.is_empty() {
+            return Err(
+                BrainstormError::syntax_error(
+                    format!("'{keyword}' outside of a loop")))
+        }
+
+        match label {
+            Some(label) if !self.loops.contains(&Some(label.clone())) => Err(
+                BrainstormError::syntax_error(
+                    format!("'{keyword}' to undefined loop label '{label}'"))),
+            _ => Ok(())
+        }
+    }
+}
+
+/// Rejects `return`, `break` and `continue` statements that have no
+/// function or loop to leave, `pub` anywhere but the top of a file and int
+/// literals that do not fit in an int, before any of the program runs.
+pub fn check_program(Program::Body{statements}: &Program) 
+    -> Result<(), BrainstormError> {
+    for statement in statements {
+        let definition = match &statement.kind {
+            StatementKind::Public { statement } => statement,
+            _ => statement,
+        };
+        check_statement(definition, &Scope::default())
+            .map_err(|e| e.at(&statement.span))?;
+    }
+
+    Ok(())
+}
+
+fn check_statements(statements: &[Statement], scope: &Scope) 
+    -> Result<(), BrainstormError> {
+    for statement in statements {
+        check_statement(statement, scope).map_err(|e| e.at(&statement.span))?;
+    }
+
+    Ok(())
+}
+
+fn check_statement(statement: &Statement, scope: &Scope) 
+    -> Result<(), BrainstormError> {
+    match &statement.kind {
+        StatementKind::Import { .. } 
+        | StatementKind::EnumDefinition { .. } => Ok(()),
+        StatementKind::Expression { expression } => check_expression(expression),
+        StatementKind::Assignment { lhs, rhs } => {
+            check_expression(lhs)?;
+            check_expression(rhs)
+        },
+        StatementKind::OperatorAssignment { rhs, .. } 
+        | StatementKind::Declaration { rhs, .. } => check_expression(rhs),
+        StatementKind::If { params } => {
+            check_expression(&params.condition)?;
+            check_statements(&params.statements, scope)?;
+
+            let (elif_conditions, elif_statements) = &params.elif_data;
+            for condition in elif_conditions {
+                check_expression(condition)?;
+            }
+            for statements in elif_statements {
+                check_statements(statements, scope)?;
+            }
+
+            match &params.else_statements {
+                Some(else_statements) => check_statements(else_statements, scope),
+                None => Ok(())
+            }
+        },
+        StatementKind::While { label, condition, statements } => {
+            check_expression(condition)?;
+            check_statements(statements, &scope.enter_loop(label))
+        },
+        StatementKind::For { params } => {
+            check_expression(&params.iterate_expression)?;
+            check_statements(&params.statements, &scope.enter_loop(&params.label))
+        },
+        StatementKind::Match { subject, arms } => {
+            check_expression(subject)?;
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    check_expression(guard)?;
+                }
+                check_statements(&arm.statements, scope)?;
+            }
+            Ok(())
+        },
+        StatementKind::Throw { value } => check_expression(value),
+        StatementKind::Try { statements, catch, finally } => {
+            check_statements(statements, scope)?;
+            if let Some(catch) = catch {
+                check_statements(&catch.statements, scope)?;
+            }
+            match finally {
+                Some(finally) => check_statements(finally, scope),
+                None => Ok(())
+            }
+        },
+        StatementKind::FunctionDefinition { statements, .. } 
+            => check_statements(statements, &Scope::function()),
+        StatementKind::Public { .. } => Err(
+            BrainstormError::syntax_error("'pub' is only allowed at the top of a file")),
+        StatementKind::Return { expression } => {
+            if !scope.in_function {
+                return Err(
+                    BrainstormError::syntax_error("'return' outside of a function"))
+            }
+
+            match expression {
+                Some(expression) => check_expression(expression),
+                None => Ok(())
+            }
+        },
+        StatementKind::Break { label } => scope.check_jump("break", label),
+        StatementKind::Continue { label } => scope.check_jump("continue", label),
+    }
+}
+
+// Expressions only hold statements inside class definitions, but those can
+// be nested anywhere an expression is allowed.
+fn check_expression(expression: &Expression) -> Result<(), BrainstormError> {
+    match &expression.kind {
+        ExpressionKind::Int { v } => match i32::try_from(*v) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(
+                BrainstormError::syntax_error(
+                    format!("integer literal `{v}` does not fit in an int"))
+                .at(&expression.span)),
+        },
+        ExpressionKind::String { .. }
+        | ExpressionKind::Boolean { .. }
+        | ExpressionKind::Float { .. }
+        | ExpressionKind::Character { .. }
+        | ExpressionKind::Identifier { .. }
+        | ExpressionKind::FieldAccess { .. } => Ok(()),
+        ExpressionKind::List { items } => {
+            for item in items {
+                check_expression(&item.expression)?;
+            }
+            Ok(())
+        },
+        ExpressionKind::Set { items } => {
+            for item in items {
+                check_expression(item)?;
+            }
+            Ok(())
+        },
+        ExpressionKind::Map { entries } => {
+            for (key, value) in entries {
+                check_expression(key)?;
+                check_expression(value)?;
+            }
+            Ok(())
+        },
+        ExpressionKind::ClassDef { params } => {
+            if let Some(statements) = &params.init.statements {
+                check_statements(statements, &Scope::function())?;
+            }
+            for method in params.methods.values() {
+                check_statements(&method.statements, &Scope::function())?;
+            }
+            Ok(())
+        },
+        ExpressionKind::Call { function, arguments } => {
+            check_expression(function)?;
+            for argument in arguments {
+                check_expression(argument)?;
+            }
+            Ok(())
+        },
+        ExpressionKind::ObjectCreation { arguments, .. }
+        | ExpressionKind::MethodCall { arguments, .. } => {
+            for argument in arguments {
+                check_expression(argument)?;
+            }
+            Ok(())
+        },
+        ExpressionKind::Operation { lhs, rhs, .. } => {
+            check_expression(lhs)?;
+            check_expression(rhs)
+        },
+        ExpressionKind::Prefix { rhs, .. } => check_expression(rhs),
+        ExpressionKind::Lambda { body, .. } => check_expression(body),
+        ExpressionKind::Unary { operand, .. } => check_expression(operand),
+        ExpressionKind::Index { idx_exp, .. } => check_expression(idx_exp),
+        ExpressionKind::Comprehension { iterate_exp, control_exp, .. } => {
+            check_expression(iterate_exp)?;
+            check_expression(control_exp)
+        },
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use crate::error::BrainstormError;
+use crate::value::Value;
+
+/// A map from values to values that remembers the order keys were first
+/// inserted in, so that printing and iterating over it is predictable.
+#[derive(Clone,Debug,Default)]
+pub struct ValueMap {
+    entries: Vec<(Value, Value)>,
+    index: HashMap<Value, usize>,
+}
+
+impl ValueMap {
+    pub fn new() -> Self {
+        ValueMap::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        self.index.get(key).map(|&idx| &self.entries[idx].1)
+    }
+
+    pub fn contains_key(&self, key: &Value) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Sets the value for `key`. A key that is already present keeps its 
+    /// place in the order.
+    pub fn insert(&mut self, key: Value, value: Value) -> Result<(), BrainstormError> {
+        if !key.is_hashable() {
+            return Err(
+                BrainstormError::type_error(
+                    format!("{} cannot be a map key or set item", key.type_name())))
+        }
+
+        match self.index.get(&key) {
+            Some(&idx) => self.entries[idx].1 = value,
+            None => self.push(key, value),
+        }
+        Ok(())
+    }
+
+    // Adds an entry for a key known to be hashable and not yet present
+    fn push(&mut self, key: Value, value: Value) {
+        self.index.insert(key.clone(), self.entries.len());
+        self.entries.push((key, value));
+    }
+
+    pub fn remove(&mut self, key: &Value) -> Option<Value> {
+        let idx = self.index.remove(key)?;
+        let (_, value) = self.entries.remove(idx);
+
+        for later in self.index.values_mut() {
+            if *later > idx {
+                *later -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Value, Value)> {
+        self.entries.iter()
+    }
+}
+
+impl PartialEq for ValueMap {
+    // Maps are equal when they hold the same entries, in any order
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+/// A set of values, kept in the order they were first added.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct ValueSet {
+    items: ValueMap,
+}
+
+impl ValueSet {
+    pub fn new() -> Self {
+        ValueSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, item: &Value) -> bool {
+        self.items.contains_key(item)
+    }
+
+    pub fn insert(&mut self, item: Value) -> Result<(), BrainstormError> {
+        self.items.insert(item, Value::Null)
+    }
+
+    pub fn remove(&mut self, item: &Value) -> bool {
+        self.items.remove(item).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.items.keys()
+    }
+
+    pub fn union(&self, other: &ValueSet) -> ValueSet {
+        let mut set = self.clone();
+        for item in other.iter() {
+            if !set.contains(item) {
+                set.items.push(item.clone(), Value::Null);
+            }
+        }
+        set
+    }
+
+    pub fn intersection(&self, other: &ValueSet) -> ValueSet {
+        self.filtered(|item| other.contains(item))
+    }
+
+    pub fn difference(&self, other: &ValueSet) -> ValueSet {
+        self.filtered(|item| !other.contains(item))
+    }
+
+    fn filtered(&self, keep: impl Fn(&Value) -> bool) -> ValueSet {
+        let mut set = ValueSet::new();
+        for item in self.iter().filter(|item| keep(item)) {
+            set.items.push(item.clone(), Value::Null);
+        }
+        set
+    }
+}
@@ -1,4 +1,4 @@
 // Error margin used for floating point equality comparisons
 //pub const FP_ERROR_MARGIN: f64 = 0.000_000_000_000_001;
 
-pub const KEYWORDS: &[&str] = &["class", "init"];
+pub const KEYWORDS: &[&str] = &["class", "init", "enum"];
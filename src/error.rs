@@ -0,0 +1,258 @@
+use std::fmt;
+
+use lalrpop_util::lexer::Token;
+use lalrpop_util::ParseError;
+
+use crate::source::{snippet, SourceFile, Span};
+use crate::value::Value;
+
+// Binary operator tokens. Parsers expect all of them after any complete
+// expression, so they are reported collectively instead of one by one.
+const OPERATOR_TOKENS: &[&str] = &["+", "-", "*", "/", "%", "**", "~/", "<", ">", 
+                                   "<=", ">=", "in", "==", "!=", "&&", "||"];
+
+/// An error raised while parsing or evaluating a program, located at the
+/// innermost statement or expression that produced it.
+#[derive(Clone,Debug)]
+pub enum BrainstormError {
+    Syntax{message: String, span: Option<Span>},
+    Name{message: String, span: Option<Span>},
+    Type{message: String, span: Option<Span>},
+    Index{message: String, span: Option<Span>},
+    Arity{message: String, span: Option<Span>},
+    Arithmetic{message: String, span: Option<Span>},
+    Match{message: String, span: Option<Span>},
+    // `chain` holds the imports that led to the file the error is in, 
+    // innermost first
+    Import{message: String, span: Option<Span>, chain: Vec<Span>},
+    Io{message: String, span: Option<Span>},
+    // A value thrown by the program, described by `message` if uncaught
+    Raised{message: String, value: Box<Value>, span: Option<Span>},
+}
+
+impl BrainstormError {
+    pub fn syntax_error(message: impl Into<String>) -> Self {
+        BrainstormError::Syntax{message: message.into(), span: None}
+    }
+
+    pub fn name_error(message: impl Into<String>) -> Self {
+        BrainstormError::Name{message: message.into(), span: None}
+    }
+
+    pub fn type_error(message: impl Into<String>) -> Self {
+        BrainstormError::Type{message: message.into(), span: None}
+    }
+
+    pub fn index_error(message: impl Into<String>) -> Self {
+        BrainstormError::Index{message: message.into(), span: None}
+    }
+
+    pub fn arity_error(message: impl Into<String>) -> Self {
+        BrainstormError::Arity{message: message.into(), span: None}
+    }
+
+    pub fn arithmetic_error(message: impl Into<String>) -> Self {
+        BrainstormError::Arithmetic{message: message.into(), span: None}
+    }
+
+    pub fn match_error(message: impl Into<String>) -> Self {
+        BrainstormError::Match{message: message.into(), span: None}
+    }
+
+    pub fn import_error(message: impl Into<String>) -> Self {
+        BrainstormError::Import{message: message.into(), span: None, chain: vec![]}
+    }
+
+    pub fn io_error(message: impl Into<String>) -> Self {
+        BrainstormError::Io{message: message.into(), span: None}
+    }
+
+    pub fn raised(value: Value) -> Self {
+        BrainstormError::Raised{message: value.to_string(), 
+                                value: Box::new(value), 
+                                span: None}
+    }
+
+    /// Describes a parse failure in language terms, located in `source`.
+    pub fn from_parse_error(source: &SourceFile, 
+                            error: ParseError<usize, Token<'_>, (usize, String)>) 
+        -> Self {
+        let (offset, message) = match error {
+            ParseError::InvalidToken { location } => {
+                let c = source.text[location..].chars().next().unwrap_or(' ');
+                (location, format!("unrecognized character `{c}`"))
+            },
+            ParseError::UnrecognizedEof { location, expected }
+                => (location, format!("{}, found end of file",
+                                      describe_expected(&expected))),
+            ParseError::UnrecognizedToken { token: (start, token, _), expected }
+                => (start, format!("{}, found `{}`",
+                                   describe_expected(&expected), token.1)),
+            ParseError::ExtraToken { token: (start, token, _) }
+                => (start, format!("unexpected `{}`", token.1)),
+            ParseError::User { error } => error,
+        };
+
+        BrainstormError::Syntax{message, span: Some(source.span(offset))}
+    }
+
+    pub fn message(&self) -> &str {
+        self.parts().0
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        self.parts().1.as_ref()
+    }
+
+    /// Attaches `span` unless a more precise location is already known.
+    pub fn at(mut self, span: &Span) -> Self {
+        let location = self.parts_mut().1;
+        if location.is_none() {
+            *location = Some(span.clone());
+        }
+        self
+    }
+
+    /// Records that an import error located in another file was reached 
+    /// through the import statement at `span`.
+    pub fn imported_from(mut self, span: &Span) -> Self {
+        if let BrainstormError::Import { span: Some(_), chain, .. } = &mut self {
+            chain.push(span.clone());
+        }
+        self
+    }
+
+    /// Name of the error category, as shown to the user.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BrainstormError::Syntax { .. } => "Syntax",
+            BrainstormError::Name { .. } => "Name",
+            BrainstormError::Type { .. } => "Type",
+            BrainstormError::Index { .. } => "Index",
+            BrainstormError::Arity { .. } => "Arity",
+            BrainstormError::Arithmetic { .. } => "Arithmetic",
+            BrainstormError::Match { .. } => "Match",
+            BrainstormError::Import { .. } => "Import",
+            BrainstormError::Io { .. } => "IO",
+            BrainstormError::Raised { .. } => "Raised",
+        }
+    }
+
+    /// Status the interpreter exits with when this error is not handled.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BrainstormError::Syntax { .. } => 2,
+            BrainstormError::Name { .. } => 3,
+            BrainstormError::Type { .. } => 4,
+            BrainstormError::Index { .. } => 5,
+            BrainstormError::Arity { .. } => 6,
+            BrainstormError::Import { .. } => 7,
+            BrainstormError::Io { .. } => 8,
+            BrainstormError::Raised { .. } => 9,
+            BrainstormError::Arithmetic { .. } => 10,
+            BrainstormError::Match { .. } => 11,
+        }
+    }
+
+    fn parts(&self) -> (&str, &Option<Span>) {
+        match self {
+            BrainstormError::Syntax { message, span }
+            | BrainstormError::Name { message, span }
+            | BrainstormError::Type { message, span }
+            | BrainstormError::Index { message, span }
+            | BrainstormError::Arity { message, span }
+            | BrainstormError::Arithmetic { message, span }
+            | BrainstormError::Match { message, span }
+            | BrainstormError::Import { message, span, .. }
+            | BrainstormError::Io { message, span }
+            | BrainstormError::Raised { message, span, .. } => (message, span),
+        }
+    }
+
+    fn parts_mut(&mut self) -> (&mut String, &mut Option<Span>) {
+        match self {
+            BrainstormError::Syntax { message, span }
+            | BrainstormError::Name { message, span }
+            | BrainstormError::Type { message, span }
+            | BrainstormError::Index { message, span }
+            | BrainstormError::Arity { message, span }
+            | BrainstormError::Arithmetic { message, span }
+            | BrainstormError::Match { message, span }
+            | BrainstormError::Import { message, span, .. }
+            | BrainstormError::Io { message, span }
+            | BrainstormError::Raised { message, span, .. } => (message, span),
+        }
+    }
+}
+
+fn describe_expected(expected: &[String]) -> String {
+    let mut names: Vec<String> = vec![];
+    let mut wants_operator = false;
+    // `in` is also the keyword of for loops, where no other operator fits
+    let only_in = !expected.iter()
+        .any(|t| t != "\"in\"" && OPERATOR_TOKENS.contains(&t.trim_matches('"')));
+
+    for token in expected {
+        let token = token.as_str();
+        if let Some(literal) = token.strip_prefix('"')
+                                    .and_then(|t| t.strip_suffix('"')) {
+            if OPERATOR_TOKENS.contains(&literal) && !(only_in && literal == "in") {
+                wants_operator = true;
+            } else {
+                names.push(format!("`{literal}`"));
+            }
+            continue;
+        }
+
+        let name = match token {
+            r##"r#"[_a-zA-Z][_a-zA-Z0-9]*"#"## => "an identifier",
+            r##"r#"[0-9]+"#"## => "an integer",
+            r##"r#"[0-9]+[.][0-9]+"#"## => "a float",
+            r##"r#"\"[^\\\"]*\""#"## => "a string",
+            r##"r#"'[^\\']'"#"## => "a character",
+            _ => "a literal",
+        };
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+
+    // An expression statement can also continue as an assignment or a call,
+    // but a missing `;` is by far the likelier mistake.
+    let ends_statement = names.iter().any(|n| n == "`;`")
+        && names.iter().all(|n| n == "`;`" || n == "`=`" || n == "`(`");
+    if ends_statement {
+        return "expected `;` after expression".to_string();
+    }
+    if wants_operator {
+        names.push("an operator".to_string());
+    }
+
+    match names.as_slice() {
+        [] => "unexpected token".to_string(),
+        [name] => format!("expected {name}"),
+        [init @ .., last] => format!("expected one of {} or {last}",
+                                     init.join(", ")),
+    }
+}
+
+impl fmt::Display for BrainstormError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} error: {}", self.kind(), self.message())?;
+
+        let Some(span) = self.span() else { return Ok(()) };
+        let gutter = " ".repeat(span.line.to_string().len());
+        write!(f, "\n{gutter}--> {}:{}:{}", span.file, span.line, span.column)?;
+
+        if let Some(snippet) = snippet(span) {
+            write!(f, "\n{snippet}")?;
+        }
+        if let BrainstormError::Import { chain, .. } = self {
+            for import in chain {
+                write!(f, "\n{gutter} = imported from {}:{}:{}", 
+                       import.file, import.line, import.column)?;
+            }
+        }
+        Ok(())
+    }
+}
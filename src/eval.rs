@@ -1,82 +1,118 @@
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
-
-use crate::ast::{ClassField, Expression, IfBranch, ListItem, 
-                 Operator, Program, Statement};
+use std::rc::Rc;
+
+use crate::builtins::insert_builtins;
+use crate::check::check_program;
+use crate::types::check_types;
+use crate::collections::{ValueMap, ValueSet};
+use crate::ast::{ClassField, EnumVariant, Expression, ExpressionKind, IfBranch, 
+                 ImportedNames, ListItem, Operator, Pattern, PatternKind, Program, 
+                 Statement, StatementKind};
+use crate::error::BrainstormError;
 use crate::parser::ProgramParser;
-use crate::read_file;
+use crate::imports::{import_module, is_bound_from, record_binding};
+use crate::source::{SourceFile, Span};
 use crate::value::Value;
 use crate::constants::KEYWORDS;
-use crate::{println_, print_, range_step, range};
+
+/// The evaluator state handed to native builtins, so that they can call
+/// back into functions written in the language.
+pub struct Interpreter<'a> {
+    enviornment: &'a mut HashMap<String, Value>,
+    importing: bool,
+}
+
+impl Interpreter<'_> {
+    pub fn call(&mut self, function: &Value, arguments: Vec<Value>) 
+        -> Result<Value, BrainstormError> {
+        call_function(self.enviornment, function, arguments, self.importing)
+    }
+}
+
+/// How control leaves a statement: on to the next one, out of the
+/// enclosing function with a value, or out of a loop, optionally labeled.
+enum Flow {
+    Next,
+    Return(Box<Value>),
+    Break(Option<String>),
+    Continue(Option<String>),
+}
+
+pub fn parse_program(source: &SourceFile) -> Result<Program, BrainstormError> {
+    let program = ProgramParser::new()
+        .parse(source, &source.text)
+        .map_err(|e| BrainstormError::from_parse_error(source, e))?;
+
+    check_program(&program)?;
+    check_types(&program)?;
+    Ok(program)
+}
 
 pub fn eval_program(enviornment: &mut HashMap<String, Value>, 
                     Program::Body{statements}: &Program, importing: bool) 
-                    -> Result<(), String> {
+                    -> Result<(), BrainstormError> {
         
-        eval_statements(enviornment, statements, importing)
+        // check_program has made sure no other flow can reach the top level
+        eval_statements(enviornment, statements, importing)?;
+        Ok(())
 }
 
 fn assign(enviornment: &mut HashMap<String, Value>, lhs: Expression, rhs: Value)
-    -> Result<(), String> {
+    -> Result<(), BrainstormError> {
 
-    match lhs {
-        Expression::Identifier { name } => {
+    match lhs.kind {
+        ExpressionKind::Identifier { name } => {
                     if name == "_" {
                         return Ok(());
                     }
                     enviornment.insert(name.clone(), rhs);
         },
-        Expression::List { items } => {
+        ExpressionKind::List { items } => {
             let Value::List{e: new_items} = rhs 
             else { 
-                return Err("cannot destructure non-list into list".to_string()) 
+                return Err(
+                    BrainstormError::type_error("cannot destructure non-list into list")) 
             };
 
             assign_list(enviornment, items, new_items)?;
         },
-        Expression::Index { name, idx_exp} => {
+        ExpressionKind::Index { name, idx_exp} => {
             let Some(var) = enviornment.get(&name) 
-                else { return Err(format!("'{}' is not defined", name)) };
+                else { return Err(
+                    BrainstormError::name_error(format!("'{}' is not defined", name))) };
             
 
             let exp_res = 
-                match eval_expression(&mut enviornment.clone(), 
-                          &idx_exp, false){
-                    Ok(v) => v,
-                    Err(e) => return Err(e),
-            };
+                eval_expression(&mut enviornment.clone(), &idx_exp, false)?;
 
             let mut list = match var {
                 Value::List { e } => e.clone(),
 
+                Value::Map { m } => {
+                    let mut m = m.clone();
+                    m.insert(exp_res, rhs)?;
+                    enviornment.insert(name, Value::Map{m});
+                    return Ok(())
+                },
                 Value::Str { .. } 
-                    => return Err("Cannot assign to String Index".to_string()),
-                Value::Null 
-                    => return Err("Cannot index Null".to_string()),
-                Value::Int { .. } 
-                    => return Err("Cannot index Int".to_string()),
-                Value::Bool { .. } 
-                    => return Err("Cannot index Boolean".to_string()),
-                Value::Char { .. } 
-                    => return Err("Cannot index Char".to_string()),
-                Value::Function { .. } 
-                    => return Err("Cannot index Function".to_string()),
-                Value::UserDefFunction { .. } 
-                    => return Err("Cannot index Function".to_string()),
-                Value::Float { .. } 
-                    => return Err("Cannot index Float".to_string()),
-                Value::Object { .. }
-                    => return Err("Cannot index Object".to_string()),
+                    => return Err(
+                        BrainstormError::type_error("Cannot assign to String Index")),
+                v => return Err(
+                    BrainstormError::type_error(
+                        format!("Cannot index {}", v.type_name()))),
             };
 
             let Value::Int { v: idx } = exp_res 
-                else { return Err("Index must be of type int".to_string()) };
+                else { return Err(
+                    BrainstormError::type_error("Index must be of type int")) };
 
             let usize_idx = idx.unsigned_abs() as usize;
             let length = list.len();
-            if usize_idx > length {
-                return Err(format!("Index {} is out of bounds", idx));
+            if usize_idx >= length {
+                return Err(
+                    BrainstormError::index_error(
+                        format!("Index {} is out of bounds", idx)));
             }
 
             if idx < 0 {
@@ -88,17 +124,19 @@ fn assign(enviornment: &mut HashMap<String, Value>, lhs: Expression, rhs: Value)
 
             enviornment.insert(name, Value::List { e: list });
         },
-        Expression::FieldAccess { name, field } => {
+        ExpressionKind::FieldAccess { name, field } => {
             if name == "this" {
                 let Some(_) = enviornment.get(&field) else {
-                    return Err(format!("No such field \"{}\"", field))
+                    return Err(
+                        BrainstormError::name_error(
+                            format!("No such field \"{}\"", field)))
                 };
                 enviornment.insert(field, rhs);
                 return Ok(())
             }
 
             let Some(obj) = enviornment.get(&name) else {
-                return Err(format!("{} is undefined", name))
+                return Err(BrainstormError::name_error(format!("{} is undefined", name)))
             };
 
             let Value::Object { name: class_name, 
@@ -106,20 +144,23 @@ fn assign(enviornment: &mut HashMap<String, Value>, lhs: Expression, rhs: Value)
                                 init, methods, 
                                 parent_class } 
                                 = obj else { 
-                return Err(format!("{} is not an object", name)) 
+                return Err(
+                    BrainstormError::type_error(format!("{} is not an object", name))) 
             };
 
             let Some(data) = obj_fields.get(&field) else {
-                return Err(format!("{} has no field {}", name, field))
+                return Err(
+                    BrainstormError::name_error(
+                        format!("{} has no field {}", name, field)))
             };
 
             if data.is_private {
-                return Err("Cannot access private field".to_string())
+                return Err(BrainstormError::name_error("Cannot access private field"))
             }
 
             let new_field = ClassField{
-                is_private: data.is_private,
                 value: rhs,
+                ..data.clone()
             };
 
             let mut new_fields = obj_fields.clone();
@@ -135,29 +176,47 @@ fn assign(enviornment: &mut HashMap<String, Value>, lhs: Expression, rhs: Value)
 
             return Ok(())
         }, 
-        Expression::Int { .. } 
-            => return Err("Cannot assign to a Integer literal".to_string()),
-        Expression::String { .. } 
-            => return Err("Cannot assign to a String literal".to_string()),
-        Expression::Boolean { ..} 
-            => return Err("Cannot assign to a Boolean literal".to_string()),
-        Expression::Float { .. } 
-            => return Err("Cannot assign to a Float literal".to_string()),
-        Expression::Character { .. } 
-            => return Err("Cannot assign to a Character literal".to_string()),
-        Expression::Call { ..} 
-            => return Err("Cannot assign to a Function call".to_string()),
-        Expression::Operation { .. } 
-            => return Err("Cannot assign to a Operation".to_string()),
-        Expression::Prefix { .. } 
-            => return Err("Cannot assign to a Prefix".to_string()),
-        Expression::Comprehension { .. } 
-            => return Err("Cannot assign to a Comprehension".to_string()),
-        Expression::ClassDef { .. }
-            => return Err("Cannot assign to a Class".to_string()),
-        Expression::ObjectCreation { .. } 
-            => return Err("Cannot assign to a Object".to_string()),
-        Expression::MethodCall { .. } => {
+        ExpressionKind::Int { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Integer literal")),
+        ExpressionKind::String { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a String literal")),
+        ExpressionKind::Boolean { ..} 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Boolean literal")),
+        ExpressionKind::Float { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Float literal")),
+        ExpressionKind::Character { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Character literal")),
+        ExpressionKind::Call { ..} 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Function call")),
+        ExpressionKind::Lambda { .. } 
+            => return Err(BrainstormError::syntax_error("Cannot assign to a Lambda")),
+        ExpressionKind::Map { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Map literal")),
+        ExpressionKind::Set { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Set literal")),
+        ExpressionKind::Operation { .. } 
+            => return Err(BrainstormError::syntax_error("Cannot assign to a Operation")),
+        ExpressionKind::Prefix { .. } 
+            => return Err(BrainstormError::syntax_error("Cannot assign to a Prefix")),
+        ExpressionKind::Unary { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Unary operation")),
+        ExpressionKind::Comprehension { .. } 
+            => return Err(
+                BrainstormError::syntax_error("Cannot assign to a Comprehension")),
+        ExpressionKind::ClassDef { .. }
+            => return Err(BrainstormError::syntax_error("Cannot assign to a Class")),
+        ExpressionKind::ObjectCreation { .. } 
+            => return Err(BrainstormError::syntax_error("Cannot assign to a Object")),
+        ExpressionKind::MethodCall { .. } => {
             todo!();
         }
     }
@@ -168,12 +227,14 @@ fn assign(enviornment: &mut HashMap<String, Value>, lhs: Expression, rhs: Value)
 }
 
 fn assign_list(enviornment: &mut HashMap<String, Value>, lhs: Vec<ListItem>, 
-    rhs: Vec<Value>) -> Result<(), String> {
+    rhs: Vec<Value>) -> Result<(), BrainstormError> {
 
     if lhs.len() > rhs.len() {
-        return Err(format!("Cannot assign {} values to {} items", 
-                    rhs.len(), 
-                    lhs.len()))
+        return Err(
+            BrainstormError::arity_error(
+                format!("Cannot assign {} values to {} items", 
+                        rhs.len(), 
+                        lhs.len())))
     }
 
     let mut assign_name_queue: Vec<ListItem> = vec![];
@@ -182,9 +243,11 @@ fn assign_list(enviornment: &mut HashMap<String, Value>, lhs: Vec<ListItem>,
     for x in 0..rhs.len(){
         if x == lhs.len() - 1 && lhs.len() != rhs.len(){
             if !lhs[x].is_pack {
-                return Err(format!("Cannot assign {} values to {} items", 
-                    rhs.len(), 
-                    lhs.len()))
+                return Err(
+                    BrainstormError::arity_error(
+                        format!("Cannot assign {} values to {} items", 
+                                rhs.len(), 
+                                lhs.len())))
             }
 
             assign_name_queue.push(lhs[x].clone());
@@ -193,7 +256,8 @@ fn assign_list(enviornment: &mut HashMap<String, Value>, lhs: Vec<ListItem>,
         }
 
         if lhs[x].is_spread {
-            return Err("Cannot use spread in list assignment".to_string())
+            return Err(
+                BrainstormError::syntax_error("Cannot use spread in list assignment"))
         }
 
         assign_name_queue.push(lhs[x].clone());
@@ -201,7 +265,7 @@ fn assign_list(enviornment: &mut HashMap<String, Value>, lhs: Vec<ListItem>,
     }
 
     for (ListItem{expression, .. }, value) in
-        assign_name_queue.into_iter().zip(assign_value_queue.into_iter()) {
+        assign_name_queue.into_iter().zip(assign_value_queue) {
         
         assign(enviornment, expression, value)?;
     }
@@ -211,51 +275,52 @@ fn assign_list(enviornment: &mut HashMap<String, Value>, lhs: Vec<ListItem>,
 }
 
 fn eval_statement(enviornment: &mut HashMap<String, Value>, 
-    statement: &Statement, importing: bool) -> Result<(), String> {
-    match statement {
-        Statement::Expression{expression} => {
+    statement: &Statement, importing: bool) -> Result<Flow, BrainstormError> {
+    eval_statement_kind(enviornment, statement, importing)
+        .map_err(|e| e.at(&statement.span))
+}
+
+fn eval_statement_kind(enviornment: &mut HashMap<String, Value>, 
+    statement: &Statement, importing: bool) -> Result<Flow, BrainstormError> {
+    match &statement.kind {
+        StatementKind::Expression{expression} => {
             eval_expression(enviornment, expression, importing)?;
         },
-        Statement::Assignment{lhs, rhs} => {
-            let v = 
-                match eval_expression(enviornment, rhs, importing) {
-                    Ok(v) => v,
-                    Err(e) => return Err(e),
-                };
+        StatementKind::Assignment{lhs, rhs} => {
+            let v = eval_expression(enviornment, rhs, importing)?;
             
             assign(enviornment, lhs.clone(), v)?;
         },
-        Statement::OperatorAssignment{name, operator, rhs} => {
+        StatementKind::Declaration{name, rhs, ..} => {
+            let v = eval_expression(enviornment, rhs, importing)?;
+
+            enviornment.insert(name.clone(), v);
+        },
+        StatementKind::OperatorAssignment{name, operator, rhs} => {
             let lhs = 
                 match enviornment.get(name) {
                     Some(v) => v.clone(),
-                    None => return Err(format!("'{}' is not defined", &name))
+                    None => return Err(
+                        BrainstormError::name_error(
+                            format!("'{}' is not defined", &name)))
                 };
 
-            let rhs = match eval_expression(enviornment, rhs, importing) {
-                    Ok(v) => v,
-                    Err(e) => return Err(e)
-                };
+            let rhs = eval_expression(enviornment, rhs, importing)?;
 
-            let v = 
-                match operate(operator, &lhs, &rhs) {
-                    Ok(Value::Null) 
-                        => return Err(format!("Cannot operate on {}", name)),
-                    Ok(v) => v,
-                    Err(e) => return Err(e)
-                };
+            let v = operate(operator, &lhs, &rhs)?;
 
             enviornment.insert(name.clone(), v);
         },
-        Statement::If{params} => {
+        StatementKind::If{params} => {
             match eval_expression(enviornment, &params.condition, importing) {
                 Ok(Value::Bool{b: true}) 
-                    => eval_statements(enviornment, &params.statements, 
-                                       importing)?,
+                    => return eval_statements(enviornment, &params.statements, 
+                                              importing),
                 Ok(Value::Bool{b: false}) => {
                     let (elif_conditions, elif_statements ) = &params.elif_data;
                     if !elif_conditions.is_empty() {
                         let condition = elif_conditions[0].clone();
+                        let span = condition.span.clone();
                         let statement = elif_statements[0].clone();
 
                         let next_iter = IfBranch{
@@ -266,101 +331,131 @@ fn eval_statement(enviornment: &mut HashMap<String, Value>,
                                         elif_statements[1..].to_vec())
                         };
 
-                        eval_statement(enviornment, 
-                            &Statement::If{params: next_iter}, importing)?;
+                        let next_statement = Statement::new(
+                            StatementKind::If{params: next_iter}, span);
+
+                        return eval_statement(enviornment, &next_statement, importing)
                     }else if let Some(else_statements) = 
                         &params.else_statements { 
-                            eval_statements(enviornment, else_statements, 
-                                            importing)?;
+                            return eval_statements(enviornment, else_statements, 
+                                                   importing)
                     }
                 },
-                _ => return Err("Condition must be of type 'bool'".to_string()),
+                Err(e) => return Err(e),
+                _ => return Err(
+                    BrainstormError::type_error("Condition must be of type 'bool'")),
             }
         },
-        Statement::While{condition, statements} => {            
+        StatementKind::While{label, condition, statements} => {            
             loop{
                 let b = 
                     match eval_expression(enviornment, condition, importing) {
                         Ok(Value::Bool{b}) => b ,
                         Err(e) => return Err(e),
                         _ => return Err(
-                            "Condition must be of type 'bool'".to_string()),
+                            BrainstormError::type_error(
+                                "Condition must be of type 'bool'")),
                     };
                             
                 if !b { break; }
                 
-                if let Err(e) 
-                    = eval_statements(enviornment, statements, importing) {
-                    return Err(e);
+                match eval_statements(enviornment, statements, importing)? {
+                    Flow::Next => {},
+                    Flow::Break(target) if leaves(&target, label) => break,
+                    Flow::Continue(target) if leaves(&target, label) => {},
+                    flow => return Ok(flow),
                 }
             }
         },
-        Statement::For{params} => {
-            let v = 
-            match &params.iterate_expression {
-                Expression::List { .. } 
-                    => eval_expression(enviornment, 
-                                      &params.iterate_expression, importing)?,
-                Expression::Identifier { .. } 
-                    => eval_expression(enviornment, 
-                                      &params.iterate_expression, importing)?,
-                Expression::Call { .. } 
-                    => eval_expression(enviornment, 
-                                      &params.iterate_expression, importing)?,
-                Expression::Int { .. } 
-                    => return Err(
-                        "Integer literals are not iterable".to_string()),
-                Expression::String { .. } 
-                    => return Err(
-                        "String literals are not iterable".to_string()),
-                Expression::Boolean { .. } 
-                    => return Err(
-                        "Boolean literals are not iterable".to_string()),
-                Expression::Float { .. } 
-                    => return Err(
-                        "Float literals are not iterable".to_string()),
-                Expression::Character { .. } 
-                    => return Err(
-                        "Character literals are not iterable".to_string()),
-                Expression::Operation { .. } 
-                    => return Err(
-                        "Operations are not iterable".to_string()),
-                Expression::Prefix { .. } 
-                    => return Err(
-                        "Prefix's are not iterable".to_string()),
-                Expression::Index { .. } 
-                    => return Err(
-                        "Indexes are not iterable".to_string()),
-                Expression::Comprehension { .. } 
-                    => return Err(
-                        "Comprehensions are not iterable".to_string()),
-                Expression::ClassDef { .. }
-                    => return Err(
-                        "Classes are not iterable".to_string()),
-                Expression::FieldAccess { .. }
-                    => return Err(
-                        "Fields are not are not iterable".to_string()),
-                Expression::ObjectCreation { .. }
-                    => return Err(
-                        "Objects are not are not iterable".to_string()),
-                Expression::MethodCall { .. } => {
-                    todo!();
+        StatementKind::For{params} => {
+            let v = eval_expression(enviornment, &params.iterate_expression, 
+                                    importing)?;
+
+            for list_item in v.items()? {
+                enviornment.insert(params.loop_var.clone(), list_item);
+
+                match eval_statements(enviornment, &params.statements, importing)? {
+                    Flow::Next => {},
+                    Flow::Break(target) if leaves(&target, &params.label) => break,
+                    Flow::Continue(target) if leaves(&target, &params.label) => {},
+                    flow => return Ok(flow),
+                }
+            }
+        },
+        StatementKind::Match{subject, arms} => {
+            let value = eval_expression(enviornment, subject, importing)?;
+
+            for arm in arms {
+                let mut bindings = vec![];
+                if !match_pattern(enviornment, &arm.pattern, &value, &mut bindings)? {
+                    continue;
                 }
-            };
 
-            let Value::List{e: iterator_list} = v 
-                else { return Err("Invalid Type".to_string())};
+                if let Some(guard) = &arm.guard {
+                    // A failed guard must not leave its bindings behind
+                    let mut guard_env = enviornment.clone();
+                    guard_env.extend(bindings.clone());
+                    match eval_expression(&mut guard_env, guard, importing)? {
+                        Value::Bool { b: true } => {},
+                        Value::Bool { b: false } => continue,
+                        v => return Err(
+                            BrainstormError::type_error(
+                                format!("Match guard must be a Bool, got {}", 
+                                        v.type_name())).at(&guard.span)),
+                    }
+                }
 
-            for list_item in iterator_list {
-                enviornment.insert(params.loop_var.clone(), list_item);
+                enviornment.extend(bindings);
+                return eval_statements(enviornment, &arm.statements, importing);
+            }
 
-                eval_statements(enviornment, &params.statements, importing)?;
+            return Err(
+                BrainstormError::match_error(
+                    format!("No pattern matches the value {}", value)))
+        },
+        StatementKind::Throw{value} => {
+            return match eval_expression(enviornment, value, importing)? {
+                // Rethrowing a caught error keeps its kind and location
+                Value::Error { error } => Err(*error),
+                value => Err(BrainstormError::raised(value)),
             }
         },
-        Statement::FunctionDefinition { name, arguments, 
-                                        statements, return_expression } => {
-            if enviornment.get(name).is_some() {
-                return Err("Function '{}' is already defined!".to_string());
+        StatementKind::Try{statements, catch, finally} => {
+            let mut result = eval_statements(enviornment, statements, importing);
+
+            if let (Err(error), Some(catch)) = (&result, catch) {
+                enviornment.insert(catch.name.clone(), caught_value(error.clone()));
+                result = eval_statements(enviornment, &catch.statements, importing);
+            }
+
+            // A jump out of `finally` replaces whatever the rest produced
+            if let Some(finally) = finally {
+                match eval_statements(enviornment, finally, importing)? {
+                    Flow::Next => {},
+                    flow => return Ok(flow),
+                }
+            }
+
+            return result;
+        },
+        StatementKind::Return{expression} => {
+            let v = match expression {
+                Some(expression) 
+                    => eval_expression(enviornment, expression, importing)?,
+                None => Value::Null,
+            };
+
+            return Ok(Flow::Return(Box::new(v)))
+        },
+        StatementKind::Break{label} => return Ok(Flow::Break(label.clone())),
+        StatementKind::Continue{label} => return Ok(Flow::Continue(label.clone())),
+        StatementKind::FunctionDefinition { name, arguments, statements, .. } => {
+            // Builtins can be replaced by functions of the same name
+            if enviornment.get(name).is_some_and(
+                |defined| !matches!(defined, Value::Function { .. })) {
+                return Err(
+                    BrainstormError::name_error(
+                        format!("Function '{}' is already defined!", name)));
             }
 
             enviornment.insert(name.to_string(), 
@@ -368,125 +463,545 @@ fn eval_statement(enviornment: &mut HashMap<String, Value>,
                                     name: name.to_string(),
                                     statements: statements.clone(),
                                     arguments: arguments.clone(),
-                                    return_expression: return_expression.clone()
+                                    module: None,
                                 });
         },
-        Statement::Import{path} => {
-            let args : Vec<String> =  std::env::args().collect();
-            let origin = Path::new(& args[1]);
-            let relative = Path::new(path);
-            let parent = origin.parent().expect("no parent found");
-            let target = parent.join(relative);
-            
-            let external_code = {
-                if let Ok(real_path) = fs::canonicalize(target) {
-                    match read_file(real_path) {
-                        Ok(f) => f,
-                        Err(_) => return Err(format!("Error opening file at {}", 
-                                           path)),
-                    }
-                } else {
-                    let mut env_path = std::env::var("BRNSTM_LIB")
-                                            .expect("BRNSTM_LIB var not set");
-                    env_path.push('/');
-                    env_path.push_str(path);
-                    if let Ok(real_path) = fs::canonicalize(env_path) {
-                        match read_file(real_path) {
-                            Ok(f) => f,
-                            Err(_) => return Err(format!("Error opening file at {}", 
-                                               path)),
-                        }
-                    } else {
-                        return Err(format!("Error opening file at {}", path))
-                    }
+        StatementKind::EnumDefinition { name, variants } => {
+            if enviornment.get(name).is_some() {
+                return Err(
+                    BrainstormError::name_error(
+                        format!("'{}' is already defined!", name)));
+            }
+
+            for (idx, variant) in variants.iter().enumerate() {
+                if variants[..idx].iter().any(|v| v.name == variant.name) {
+                    return Err(
+                        BrainstormError::name_error(
+                            format!("Variant '{}' is defined twice in '{}'", 
+                                    variant.name, name)));
                 }
-            };
+            }
 
-            let ast = ProgramParser::new().parse(&external_code).unwrap();
+            enviornment.insert(name.to_string(), 
+                               Value::EnumType { 
+                                    name: name.to_string(), 
+                                    variants: variants.clone(),
+                                });
+        },
+        StatementKind::Public{statement} 
+            => return eval_statement_kind(enviornment, statement, importing),
+        StatementKind::Import{path, names} => {
+            // Errors in the imported file keep the chain of imports leading
+            // to it
+            import(enviornment, path, names, &statement.span)
+                .map_err(|e| e.imported_from(&statement.span))?;
+        },
+    }
 
-            eval_program(enviornment, &ast, true)?;
+    Ok(Flow::Next)
+}
+
+/// Runs the import of `path` made by the statement at `origin`.
+fn import(enviornment: &mut HashMap<String, Value>, path: &str, 
+          names: &ImportedNames, origin: &Span) -> Result<(), BrainstormError> {
+    let (module, members) = import_module(path, origin, eval_module)?;
+
+    match names {
+        ImportedNames::All => {
+            for (name, member) in members.iter() {
+                bring_in(enviornment, &module, name, member, origin)?;
+            }
+            Ok(())
+        },
+        ImportedNames::Module { name } => match enviornment.get(name) {
+            Some(Value::Module { members: bound, .. }) if Rc::ptr_eq(bound, &members) 
+                => Ok(()),
+            _ => define(enviornment, name, 
+                        Value::Module{path: path.to_string(), members}),
+        },
+        ImportedNames::Listed { names } => {
+            for name in names {
+                let Some(member) = members.get(name) else {
+                    return Err(
+                        BrainstormError::import_error(
+                            format!("'{}' is not defined in {}", name, path)));
+                };
+                bring_in(enviornment, &module, name, member, origin)?;
+            }
+            Ok(())
         },
     }
+}
+
+/// Runs an imported file in a scope of its own and collects the names it
+/// marks `pub`. Its functions keep running in that scope once imported.
+fn eval_module(program: &Program) -> Result<HashMap<String, Value>, BrainstormError> {
+    let mut scope = HashMap::new();
+    insert_builtins(&mut scope);
+    eval_program(&mut scope, program, true)?;
+    let scope = Rc::new(scope);
+
+    let Program::Body{statements} = program;
+    let mut members = HashMap::new();
+    for statement in statements {
+        let StatementKind::Public{statement} = &statement.kind else { continue };
+        let Some((name, value)) = defined_name(statement)
+            .and_then(|name| scope.get_key_value(name)) else { continue };
+        let member = match value {
+            Value::UserDefFunction { name, statements, arguments, module: None } 
+                => Value::UserDefFunction { 
+                    name: name.clone(), 
+                    statements: statements.clone(),
+                    arguments: arguments.clone(),
+                    module: Some(scope.clone()),
+                },
+            value => value.clone(),
+        };
+        members.insert(name.clone(), member);
+    }
+    Ok(members)
+}
+
+/// The name bound by a definition that can be marked `pub`.
+fn defined_name(statement: &Statement) -> Option<&str> {
+    match &statement.kind {
+        StatementKind::FunctionDefinition { name, .. }
+        | StatementKind::EnumDefinition { name, .. }
+        | StatementKind::Declaration { name, .. } => Some(name),
+        StatementKind::Assignment { lhs: Expression { 
+            kind: ExpressionKind::Identifier { name }, .. }, .. } => Some(name),
+        StatementKind::Expression { expression: Expression {
+            kind: ExpressionKind::ClassDef { params }, .. } } => Some(&params.name),
+        _ => None,
+    }
+}
 
+/// Binds `name`, a member of the module at `module`, unless the importing
+/// file already bound it from there.
+fn bring_in(enviornment: &mut HashMap<String, Value>, module: &Path, name: &str,
+            member: &Value, origin: &Span) -> Result<(), BrainstormError> {
+    if enviornment.contains_key(name) && is_bound_from(origin, name, module) {
+        return Ok(())
+    }
+
+    define(enviornment, name, member.clone())?;
+    record_binding(origin, name, module);
     Ok(())
 }
 
+/// Binds a name an import brings in, which must not be taken already by
+/// anything but a builtin.
+fn define(enviornment: &mut HashMap<String, Value>, name: &str, value: Value) 
+    -> Result<(), BrainstormError> {
+    if enviornment.get(name)
+        .is_some_and(|bound| !matches!(bound, Value::Function { .. })) {
+        return Err(
+            BrainstormError::name_error(format!("'{}' is already defined!", name)));
+    }
+    enviornment.insert(name.to_string(), value);
+    Ok(())
+}
+
+/// The path and the names of the module bound to `module`.
+fn module_members<'a>(enviornment: &'a HashMap<String, Value>, module: &str) 
+    -> Result<(&'a str, &'a HashMap<String, Value>), BrainstormError> {
+    match enviornment.get(module) {
+        Some(Value::Module { path, members }) => Ok((path, members)),
+        Some(_) => Err(
+            BrainstormError::type_error(format!("{} is not a module", module))),
+        None => Err(BrainstormError::name_error(format!("{} is undefined", module))),
+    }
+}
+
+/// The member `name` of the module bound to `module`.
+fn module_member(enviornment: &HashMap<String, Value>, module: &str, name: &str) 
+    -> Result<Value, BrainstormError> {
+    let (path, members) = module_members(enviornment, module)?;
+    members.get(name).cloned().ok_or_else(|| {
+        BrainstormError::name_error(format!("'{}' is not defined in {}", name, path))
+    })
+}
+
 fn eval_statements(enviornment: &mut HashMap<String, Value>, 
                    statements: &Vec<Statement>, 
-                   importing: bool) -> Result<(), String> {
+                   importing: bool) -> Result<Flow, BrainstormError> {
     
     for statement in statements {
-        eval_statement(enviornment, statement, importing)?;
+        match eval_statement(enviornment, statement, importing)? {
+            Flow::Next => {},
+            flow => return Ok(flow),
+        }
     }
 
-    Ok(())
+    Ok(Flow::Next)
 }
 
-fn eval_expression(enviornment: &mut HashMap<String, Value>, 
-    expression: &Expression, importing: bool) -> Result<Value, String>{
-    match expression {
-        Expression::Int{v} => Ok(Value::Int{v: *v}),
-        Expression::String{ s } => Ok(Value::Str{s: s.clone()}),
-        Expression::Boolean{ b } => Ok(Value::Bool{b: *b}),
-        Expression::Float{ f} => Ok(Value::Float{f: *f}),
-        Expression::Character{ c } => Ok(Value::Char{c: *c}),
-        Expression::Identifier{name} => {
-            match enviornment.get(name) {
-                Some(v) => Ok(v.clone()),
-                None => Err(format!("'{}' is not defined", &name))
+/// Creates an object of the class `class_name`, looking it and its parent
+/// classes up in `enviornment`, and runs its initializer on `vals`.
+fn create_object(enviornment: &HashMap<String, Value>, class_name: &str, 
+                 vals: Vec<Value>, importing: bool) -> Result<Value, BrainstormError> {
+    let Some(res) = enviornment.get(class_name) else {
+        return Err(
+            BrainstormError::name_error(format!("{} is undefined", class_name)))
+    };
+    
+    let Value::Object { name, fields, init, 
+                        methods, parent_class } = res 
+        else { return Err(
+            BrainstormError::type_error(
+                format!("{} is not a class", class_name))) };
+
+    if init.name.is_none() {
+        return Ok(Value::Object{name: name.clone(), 
+                                fields: fields.clone(), 
+                                init: init.clone(), 
+                                methods: methods.clone(),
+                                parent_class: parent_class.clone()});
+    }
+
+    let init_args = match &init.arguments {
+        Some(s) => s.clone(),
+        None => vec![]
+    };
+
+    if init_args.len() != vals.len() {
+        return Err(
+            BrainstormError::arity_error(
+                format!("Expected {} arguments but got {}", 
+                            init_args.len(), vals.len())))
+    }
+
+    let init_statements = match &init.statements {
+        Some(s) => s.clone(),
+        None => vec![]
+    };
+
+    let mut local_env = HashMap::<String, Value>::new();
+    
+    let mut parent_object: Option<Value> = None;
+    if parent_class.is_some(){
+        let p_name = parent_class.clone().unwrap();
+
+        let res = enviornment.get(&p_name);
+        let Some(val) = res else {
+            return Err(
+                BrainstormError::name_error(
+                    format!("{} is not defined", p_name)));
+        };
+
+        let p_obj= match val {
+            Value::Object{ .. } => val,
+            _ => return Err(
+                BrainstormError::type_error(
+                    format!("{} is not an object", p_name))) 
+        };
+
+        local_env.insert("super".to_string(), p_obj.clone());
+        parent_object = Some(p_obj.clone());
+    }
+
+    for (name, val) in init_args.iter().zip(vals) {
+        local_env.insert(name.to_string(), val);
+    }
+
+    for (name, data) in fields {
+        local_env.insert(name.to_string(), data.value.clone());
+    }
+
+    eval_statements(&mut local_env, &init_statements, importing)?;
+    
+    let mut updated_fields = HashMap::<String, ClassField>::new();
+
+    for (field, data) in fields {
+        let Some(updated_field) = local_env.get(field) else {
+            return Err(BrainstormError::name_error(format!(
+                    "An error occured when initalizing {}", field)))
+        };
+        let mut new_data = data.clone();
+
+        new_data.value = updated_field.clone();
+
+        updated_fields.insert(field.to_string(), new_data);
+    }
+
+    if let Some(obj) = parent_object {
+        let Value::Object{ ref fields, .. } = obj else {
+            return Err(
+                BrainstormError::type_error(
+                    "Dev error: Non-object assigned to parent 
+                        eval:Expression:ObjectCreation"))
+        };
+
+        for (field, data) in fields {
+            let Some(updated_field) = local_env.get(field) else {
+                return Err(BrainstormError::name_error(format!(
+                        "An error occured when initalizing {}", field)))
+            };
+            let mut new_data = data.clone();
+
+            new_data.value = updated_field.clone();
+
+            updated_fields.insert(field.to_string(), new_data);
+        }
+        let parent_field = ClassField {
+            is_private: true,
+            value: obj.clone(),
+            annotation: None,
+        };
+
+        updated_fields.insert("super".to_string(),parent_field);
+    }
+    
+
+    Ok(Value::Object{name: name.clone(), fields: updated_fields, 
+                     init: init.clone(), methods: methods.clone(),
+                     parent_class: parent_class.clone()})
+}
+
+/// Calls a function value with arguments that have already been evaluated.
+fn call_function(enviornment: &mut HashMap<String, Value>, callee: &Value, 
+                 vals: Vec<Value>, importing: bool) -> Result<Value, BrainstormError> {
+    match callee {
+        Value::Function{name, f} => {
+            if importing && (name == "print" || name == "println") {
+                return Ok(Value::Null);     
             }
+            f(&mut Interpreter{enviornment, importing}, vals)
         },
-        Expression::Call{function, arguments} =>  {
-            let vals = eval_expressions(enviornment, arguments, importing)?;
+        Value::UserDefFunction {statements, arguments, module, ..} => {
+            check_arity(arguments.len(), vals.len())?;
 
-            let Some(v) = enviornment.get(function) 
-                else { return Err(format!("'{}' is not defined", &function)) };
-            
-            let mut local_env = enviornment.clone();
+            let mut local_env = match module {
+                Some(scope) => (**scope).clone(),
+                None => enviornment.clone(),
+            };
+            for (value, name) in vals.into_iter().zip(arguments.iter()) {
+                local_env.insert(name.to_string(), value);
+            }
+            let flow = eval_statements(&mut local_env, statements, importing)?;
 
-            match v {
-                Value::Function{f, ..} => {
-                    if importing && (function == "print" || 
-                                     function == "println" ) {
+            Ok(return_value(flow))
+        },
+        Value::Lambda {arguments, body, captured} => {
+            check_arity(arguments.len(), vals.len())?;
 
-                            return Ok(Value::Null);     
-                    }
-                    f(vals)
-                },
-                Value::UserDefFunction {statements, 
-                                        arguments , return_expression, ..} => {
-                    if vals.len() != arguments.len() {
-                        return Err(format!("Expected {} arguments, got {}", 
-                                            arguments.len(), 
-                                            vals.len()))
-                    }
-                    for (value, name) in vals.iter().zip(arguments.iter()) {
-                        local_env.insert(name.to_string(), value.clone());
-                    }
-                    eval_statements(&mut local_env, statements, importing)?;
-                    
-                    match return_expression {
-                        Some(return_exp) => {
-                            match eval_expression(&mut enviornment.clone(),
-                                      return_exp, importing) {
-                                Ok(v) => Ok(v.clone()),
-                                Err(e) 
-                                    => Err(e)
-                            }
-                        },
-                        None => Ok(Value::Null)
-                    }
+            // Lambdas see the scope they were created in, not the caller's
+            let mut local_env = (**captured).clone();
+            for (value, name) in vals.into_iter().zip(arguments.iter()) {
+                local_env.insert(name.to_string(), value);
+            }
 
-                },
-                Value::Object{ .. } => {
-                    if function != "super" {
-                        return Err(format!("'{function}' is not a function"))
-                    }
+            eval_expression(&mut local_env, body, importing)
+        },
+        v => Err(
+            BrainstormError::type_error(
+                format!("{} is not a function", v.type_name())))
+    }
+}
+
+/// The value a `catch` clause sees: what was thrown, or for the
+/// interpreter's own errors an `Error` exposing their kind and message.
+fn caught_value(error: BrainstormError) -> Value {
+    match error {
+        BrainstormError::Raised { value, .. } => *value,
+        error => Value::Error { error: Box::new(error) },
+    }
+}
+
+/// Tests `value` against `pattern`, collecting the variables it binds. A
+/// pattern naming an unknown class, enum or field is an error rather than a
+/// failed match.
+fn match_pattern(enviornment: &HashMap<String, Value>, pattern: &Pattern, 
+                 value: &Value, bindings: &mut Vec<(String, Value)>) 
+    -> Result<bool, BrainstormError> {
+    match &pattern.kind {
+        PatternKind::Wildcard => Ok(true),
+        PatternKind::Literal { value: literal } => Ok(**literal == *value),
+        PatternKind::Binding { name } => {
+            bindings.push((name.clone(), value.clone()));
+            Ok(true)
+        },
+        PatternKind::List { items, rest } => {
+            let Value::List { e } = value else { return Ok(false) };
+            let fits = match rest {
+                Some(_) => e.len() >= items.len(),
+                None => e.len() == items.len(),
+            };
+            if !fits {
+                return Ok(false);
+            }
+
+            for (item, v) in items.iter().zip(e) {
+                if !match_pattern(enviornment, item, v, bindings)? {
+                    return Ok(false);
+                }
+            }
+            if let Some(rest) = rest.as_ref().filter(|rest| *rest != "_") {
+                bindings.push((rest.clone(), 
+                               Value::List { e: e[items.len()..].to_vec() }));
+            }
+            Ok(true)
+        },
+        PatternKind::Object { class_name, fields: field_patterns } => {
+            match enviornment.get(class_name) {
+                Some(Value::Object { .. }) => {},
+                Some(_) => return Err(
+                    BrainstormError::type_error(
+                        format!("{} is not a class", class_name)).at(&pattern.span)),
+                None => return Err(
+                    BrainstormError::name_error(
+                        format!("{} is undefined", class_name)).at(&pattern.span)),
+            }
+
+            let Value::Object { name, fields, parent_class, .. } = value else {
+                return Ok(false)
+            };
+            if name != class_name && parent_class.as_ref() != Some(class_name) {
+                return Ok(false);
+            }
+
+            for (field, field_pattern) in field_patterns {
+                let field_data = match fields.get(field) {
+                    Some(data) if !data.is_private => data,
+                    Some(_) => return Err(
+                        BrainstormError::name_error("Cannot access private fields!")
+                            .at(&field_pattern.span)),
+                    None => return Err(
+                        BrainstormError::name_error(
+                            format!("'{}' has no field '{}'", class_name, field))
+                            .at(&field_pattern.span)),
+                };
+                if !match_pattern(enviornment, field_pattern, 
+                                  &field_data.value, bindings)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        },
+        PatternKind::Variant { enum_name, tag, items } => {
+            let variant = match enviornment.get(enum_name) {
+                Some(Value::EnumType { variants, .. }) 
+                    => variants.iter().find(|v| v.name == *tag),
+                Some(_) => return Err(
+                    BrainstormError::type_error(
+                        format!("{} is not an enum", enum_name)).at(&pattern.span)),
+                None => return Err(
+                    BrainstormError::name_error(
+                        format!("{} is undefined", enum_name)).at(&pattern.span)),
+            };
+            let Some(variant) = variant else {
+                return Err(
+                    BrainstormError::name_error(
+                        format!("'{}' has no variant '{}'", enum_name, tag))
+                        .at(&pattern.span))
+            };
+            check_arity(variant.fields.len(), items.len())
+                .map_err(|e| e.at(&pattern.span))?;
+
+            let Value::Enum { name, tag: value_tag, payload } = value else {
+                return Ok(false)
+            };
+            if name != enum_name || value_tag != tag {
+                return Ok(false);
+            }
+
+            for (item, (_, v)) in items.iter().zip(payload) {
+                if !match_pattern(enviornment, item, v, bindings)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        },
+    }
+}
+
+/// Builds the value of an enum's variant `tag`. `vals` is `None` when the
+/// variant is named without being called, which only unit variants allow.
+fn make_variant(name: &str, variants: &[EnumVariant], tag: &str, 
+                vals: Option<Vec<Value>>) -> Result<Value, BrainstormError> {
+    let Some(variant) = variants.iter().find(|v| v.name == tag) else {
+        return Err(
+            BrainstormError::name_error(
+                format!("'{}' has no variant '{}'", name, tag)))
+    };
+
+    let vals = match vals {
+        Some(vals) => vals,
+        None if variant.fields.is_empty() => vec![],
+        None => return Err(
+            BrainstormError::type_error(
+                format!("'{}.{}' carries values and must be called", name, tag))),
+    };
+    check_arity(variant.fields.len(), vals.len())?;
+
+    Ok(Value::Enum { name: name.to_string(), 
+                     tag: tag.to_string(), 
+                     payload: variant.fields.iter().cloned().zip(vals).collect() })
+}
+
+/// Fails with the arity error for a call passing `got` arguments where
+/// `expected` are taken, as reported both before and while running.
+pub fn check_arity(expected: usize, got: usize) -> Result<(), BrainstormError> {
+    if expected != got {
+        return Err(
+            BrainstormError::arity_error(
+                format!("Expected {} arguments, got {}", expected, got)))
+    }
+    Ok(())
+}
+
+/// The value a function body produced, `Null` if it finished without
+/// returning one.
+fn return_value(flow: Flow) -> Value {
+    match flow {
+        Flow::Return(v) => *v,
+        _ => Value::Null,
+    }
+}
+
+/// Whether a `break` or `continue` to `target` leaves the loop labeled
+/// `label`. Unlabeled ones leave the innermost loop.
+fn leaves(target: &Option<String>, label: &Option<String>) -> bool {
+    target.is_none() || target == label
+}
 
-                    let exp = Expression::ObjectCreation{ 
-                                            class_name: "super".to_string(), 
-                                            arguments: arguments.clone()
-                                        };
+fn eval_expression(enviornment: &mut HashMap<String, Value>, 
+    expression: &Expression, importing: bool) -> Result<Value, BrainstormError>{
+    eval_expression_kind(enviornment, expression, importing)
+        .map_err(|e| e.at(&expression.span))
+}
+
+fn eval_expression_kind(enviornment: &mut HashMap<String, Value>, 
+    expression: &Expression, importing: bool) -> Result<Value, BrainstormError>{
+    match &expression.kind {
+        // check_program has made sure the literal fits
+        ExpressionKind::Int{v} => Ok(Value::Int{v: *v as i32}),
+        ExpressionKind::String{ s } => Ok(Value::Str{s: s.clone()}),
+        ExpressionKind::Boolean{ b } => Ok(Value::Bool{b: *b}),
+        ExpressionKind::Float{ f} => Ok(Value::Float{f: *f}),
+        ExpressionKind::Character{ c } => Ok(Value::Char{c: *c}),
+        ExpressionKind::Identifier{name} => {
+            match enviornment.get(name) {
+                Some(v) => Ok(v.clone()),
+                None => Err(
+                    BrainstormError::name_error(format!("'{}' is not defined", &name)))
+            }
+        },
+        ExpressionKind::Call{function, arguments} =>  {
+            let callee = eval_expression(enviornment, function, importing)?;
+
+            // Inside an initializer, `super(...)` initializes the parent 
+            // object and copies its fields into scope
+            if let (Value::Object{ .. }, ExpressionKind::Identifier{name}) 
+                = (&callee, &function.kind) {
+                if name == "super" {
+                    let exp = Expression::new(
+                        ExpressionKind::ObjectCreation{ 
+                            module: None,
+                            class_name: "super".to_string(), 
+                            arguments: arguments.clone()
+                        }, 
+                        expression.span.clone());
 
                     let result = eval_expression(enviornment, &exp, importing)?;
                     
@@ -498,48 +1013,84 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
                             }
                         },
                         _ => return Err(
-                            "Dev error non-object from parent initalization"
-                            .to_string())
+                            BrainstormError::type_error(
+                                "Dev error non-object from parent initalization"))
                     };
 
+                    return Ok(Value::Null)
+                }
+            }
 
-                    Ok(Value::Null)
-                },
-                _ => Err(format!("'{function}' is not a function"))
+            let vals = eval_expressions(enviornment, arguments, importing)?;
+
+            call_function(enviornment, &callee, vals, importing)
+        },
+        ExpressionKind::Map { entries } => {
+            let mut m = ValueMap::new();
+            for (key_exp, value_exp) in entries {
+                let key = eval_expression(enviornment, key_exp, importing)?;
+                let value = eval_expression(enviornment, value_exp, importing)?;
+                m.insert(key, value).map_err(|e| e.at(&key_exp.span))?;
+            }
+
+            Ok(Value::Map{m})
+        },
+        ExpressionKind::Set { items } => {
+            let mut e = ValueSet::new();
+            for item_exp in items {
+                let item = eval_expression(enviornment, item_exp, importing)?;
+                e.insert(item).map_err(|e| e.at(&item_exp.span))?;
             }
+
+            Ok(Value::Set{e})
+        },
+        ExpressionKind::Lambda { arguments, body } => {
+            Ok(Value::Lambda{arguments: arguments.clone(), body: body.clone(), 
+                             captured: Rc::new(enviornment.clone())})
         },
-        Expression::Operation { lhs, rhs, operator } => {
+        ExpressionKind::Operation { lhs, rhs, 
+                                    operator: operator @ (Operator::And | Operator::Or) } 
+            => {
+            // Only evaluate the right hand side if it decides the result
+            let b = eval_condition(enviornment, lhs, operator, importing)?;
+            if b == matches!(operator, Operator::Or) {
+                return Ok(Value::Bool{b})
+            }
+
+            let b = eval_condition(enviornment, rhs, operator, importing)?;
+            Ok(Value::Bool{b})
+        },
+        ExpressionKind::Unary { operator, operand } => {
+            let v = eval_expression(enviornment, operand, importing)?;
+
+            match (operator, v) {
+                (Operator::Not, Value::Bool{b}) => Ok(Value::Bool{b: !b}),
+                (Operator::Minus, v @ (Value::Int{..} | Value::Float{..})) 
+                    => -v,
+                (_, v) => Err(
+                    BrainstormError::type_error(
+                        format!("Cannot apply '{}' to {}", operator, v.type_name())))
+            }
+        },
+        ExpressionKind::Operation { lhs, rhs, operator } => {
             let expressions = vec![lhs, rhs];
             let mut vals = vec![];
 
             for expression in expressions {
-                match eval_expression(enviornment, expression, importing) {
-                    Ok(v) => vals.push(v),
-                    Err(e) => return Err(e),
-                }
+                vals.push(eval_expression(enviornment, expression, importing)?);
             }
 
             if let [lhs, rhs] = vals.as_slice() {
-                let new_val = operate(operator, lhs, rhs)?;
-                if new_val == Value::Null {
-                    return Err("Invalid Operation".to_string())
-                }
-                Ok(new_val)
+                operate(operator, lhs, rhs)
             }else{
-                Err("dev error: ".to_string())
+                Err(BrainstormError::type_error("dev error: "))
             }
         },
-        Expression::List { items} => {
+        ExpressionKind::List { items} => {
             let mut vals: Vec<Value> = vec![];
             
             for item in items {
-                let v = 
-                    match eval_expression(enviornment, 
-                                          &item.expression, 
-                                          importing) {
-                        Ok(v) => v,
-                        Err(e) => return Err(e)
-                    };
+                let v = eval_expression(enviornment, &item.expression, importing)?;
 
                 if !item.is_spread {
                     vals.push(v);
@@ -548,52 +1099,63 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
 
                 match v {
                     Value::List{mut e} => vals.append(&mut e),
-                    _ => return Err("only lists can be spread!".to_string())
+                    _ => return Err(
+                        BrainstormError::type_error("only lists can be spread!"))
                 }
             }
 
             Ok(Value::List{e: vals})
         },
-        Expression::Prefix { name, operator, rhs } => {
+        ExpressionKind::Prefix { name, operator, rhs } => {
             let lhs = match enviornment.get(name) {
                 Some(v) => v.clone(),
-                None => return Err(format!("'{}' is not defined", name))
+                None => return Err(
+                    BrainstormError::name_error(format!("'{}' is not defined", name)))
             };
 
-            let v = match eval_expression(enviornment, rhs, importing) {
-                Ok(v) => v,
-                Err(e) => return Err(e)
-            };
+            let v = eval_expression(enviornment, rhs, importing)?;
 
             let new_val = operate(operator, &lhs, &v)?;
-            if new_val == Value::Null {
-                return Err(format!("Cannot operate on {}", name))
-            }
             enviornment.insert(name.clone(), new_val.clone());
 
             Ok(new_val)
         },
-        Expression::Index { name, idx_exp } => {
+        ExpressionKind::Index { name, idx_exp } => {
             let Some(var) = enviornment.get(name) 
-                else { return Err(format!("'{}' is not defined", name)) };
+                else { return Err(
+                    BrainstormError::name_error(format!("'{}' is not defined", name))) };
 
             let exp_res = eval_expression(&mut enviornment.clone(), idx_exp, 
                                                  importing)?;
 
+            if let Value::Map { m } = var {
+                return match m.get(&exp_res) {
+                    Some(v) => Ok(v.clone()),
+                    None => Err(
+                        BrainstormError::index_error(
+                            format!("Key '{}' is not in the map", exp_res)))
+                }
+            }
+
             let Value::Int { v: idx } = exp_res 
-                else { return Err("Index must be of type int".to_string()) };
+                else { return Err(
+                    BrainstormError::type_error("Index must be of type int")) };
 
             let mut iterator = var.clone().into_iter();
             let length = iterator.clone().count();
 
             if iterator.value == Value::Null {
-                return Err(format!("Cannot iterate over variable {}", name))
+                return Err(
+                    BrainstormError::type_error(
+                        format!("Cannot iterate over variable {}", name)))
             }
 
             let usize_idx = idx.unsigned_abs() as usize;
 
             if usize_idx >= length {
-                return Err(format!("Index {} is out of bounds", idx))
+                return Err(
+                    BrainstormError::index_error(
+                        format!("Index {} is out of bounds", idx)))
             }
 
             if idx < 0 {
@@ -605,60 +1167,29 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
             Ok(iterator.nth(usize_idx)
                 .unwrap_or_else(|| panic!("Err retreiving value at {}", idx)))
         },
-        Expression::Comprehension { iterate_exp, var, control_exp } => {
+        ExpressionKind::Comprehension { iterate_exp, var, control_exp } => {
             let mut local_env = enviornment.clone();
             let control_val = eval_expression(&mut local_env, 
                                                       control_exp, importing)?;
 
-            match control_val {
-                Value::List { e } => {
-                    let mut output = vec![];
-                    for item in e {
-                        local_env.insert(var.to_string(), item);
-                        let iterate_exp_val = 
-                            eval_expression(&mut local_env, 
-                                             iterate_exp, importing)?;
-                        output.push(iterate_exp_val);
-                    }
-                    Ok(Value::List{e: output})
-                },
-                Value::Str { s } => {
-                    let mut output = vec![];
-                    for c in s.chars() {
-                        local_env.insert(var.to_string(), Value::Char {c});
-                        let iterate_exp_val = 
-                            eval_expression(&mut local_env, 
-                                             iterate_exp, importing)?;
-
-                        output.push(iterate_exp_val);
-                    }
-                    Ok(Value::List{e: output})
-                },
-                Value::Null 
-                    => Err("Null is not iterable".to_string()),
-                Value::Int { .. } 
-                    => Err("Int is not iterable".to_string()),
-                Value::Bool { .. } 
-                    => Err("Bool is not iterable".to_string()),
-                Value::Float { .. } 
-                    => Err("Float is not iterable".to_string()),
-                Value::Char { .. } 
-                    => Err("Char is not iterable".to_string()),
-                Value::Function { .. } 
-                    => Err("Function is not iterable".to_string()),
-                Value::UserDefFunction { .. } 
-                    => Err("Function is not iterable".to_string()),
-                Value::Object { .. }
-                    => Err("Class is not iterable".to_string())
-            }
-        },
-        Expression::ClassDef { params } => {
+            let mut output = vec![];
+            for item in control_val.items()? {
+                local_env.insert(var.to_string(), item);
+                let iterate_exp_val = 
+                    eval_expression(&mut local_env, iterate_exp, importing)?;
+                output.push(iterate_exp_val);
+            }
+            Ok(Value::List{e: output})
+        },
+        ExpressionKind::ClassDef { params } => {
             let name = &params.name;
             let fields = &params.fields;
             
             for word in KEYWORDS {
                 if word.eq(name) {
-                    return Err(format!("\"{}\" is a protected keyword", name));
+                    return Err(
+                        BrainstormError::syntax_error(
+                            format!("\"{}\" is a protected keyword", name)));
                 }
             }
 
@@ -668,7 +1199,8 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
                 for word in KEYWORDS {
                     if word.eq(key) {
                         return Err(
-                                format!("\"{}\" is a protected keyword", key));
+                            BrainstormError::syntax_error(
+                                format!("\"{}\" is a protected keyword", key)));
                     }
                 }
             }
@@ -684,15 +1216,37 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
 
             Ok(Value::Null)
         },
-        Expression::FieldAccess { name, field } => {
+        ExpressionKind::FieldAccess { name, field } => {
             let res = enviornment.get(name);
             let Some(val) = res else { 
-                return Err(format!("{} is undefined", name)) 
+                return Err(BrainstormError::name_error(format!("{} is undefined", name))) 
             };
             
             let obj_fields = match val {
                 Value::Object { name: _, fields, .. } => fields.clone(),
-                _ => return Err("Can only access fields on objects".to_string())
+                Value::Module { .. } => return module_member(enviornment, name, field),
+                Value::EnumType { name: enum_name, variants } 
+                    => return make_variant(enum_name, variants, field, None),
+                Value::Error { error } => {
+                    return match field.as_str() {
+                        "message" => Ok(Value::Str { s: error.message().to_string() }),
+                        "kind" => Ok(Value::Str { s: error.kind().to_string() }),
+                        _ => Err(
+                            BrainstormError::name_error(
+                                format!("Error has no field '{}'", field))),
+                    }
+                },
+                Value::Enum { name: enum_name, tag, payload } => {
+                    return match payload.iter().find(|(f, _)| f == field) {
+                        Some((_, value)) => Ok(value.clone()),
+                        None => Err(
+                            BrainstormError::name_error(
+                                format!("'{}.{}' has no field '{}'", 
+                                        enum_name, tag, field))),
+                    }
+                },
+                _ => return Err(
+                    BrainstormError::type_error("Can only access fields on objects"))
             };
 
             for (field_name, field_data) in obj_fields {
@@ -702,137 +1256,44 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
                 }
             }
 
-            Err("Cannot access private fields!".to_string())
+            Err(BrainstormError::name_error("Cannot access private fields!"))
         },
-        Expression::ObjectCreation { class_name, arguments } => {
-            let Some(res) = enviornment.get(class_name) else {
-                return Err(format!("{} is undefined", class_name))
-            };
-            
-            let Value::Object { name, fields, init, 
-                                methods, parent_class } = res 
-                else { return Err(format!("{} is not a class", class_name)) };
-
-            if init.name.is_none() {
-                return Ok(Value::Object{name: name.clone(), 
-                                        fields: fields.clone(), 
-                                        init: init.clone(), 
-                                        methods: methods.clone(),
-                                        parent_class: parent_class.clone()});
-            }
-
-            let init_args = match &init.arguments {
-                Some(s) => s.clone(),
-                None => vec![]
-            };
-
-            if init_args.len() != arguments.len() {
-                return Err(format!("Expected {} arguments but got {}", 
-                                    init_args.len(), arguments.len()))
-            }
-
-            let init_statements = match &init.statements {
-                Some(s) => s.clone(),
-                None => vec![]
-            };
-
-            let mut local_env = HashMap::<String, Value>::new();
-            
-            let mut parent_object: Option<Value> = None;
-            if parent_class.is_some(){
-                let p_name = parent_class.clone().unwrap();
-
-                let res = enviornment.get(&p_name);
-                let Some(val) = res else {
-                    return Err(format!("{} is not defined", p_name));
-                };
-
-                let p_obj= match val {
-                    Value::Object{ .. } => val,
-                    _ => return Err(format!("{} is not an object", p_name)) 
-                };
-
-                local_env.insert("super".to_string(), p_obj.clone());
-                parent_object = Some(p_obj.clone());
-            }
-
-            for (name, exp) in init_args.iter().zip(arguments.iter()) {
-                let val = eval_expression(&mut enviornment.clone(), 
-                                      exp, importing)?;
-                local_env.insert(name.to_string(), val);
-            }
-
-            for (name, data) in fields {
-                local_env.insert(name.to_string(), data.value.clone());
-            }
-
-            for statement in init_statements {
-                eval_statement(&mut local_env, &statement, importing)?;
-            }
-            
-            let mut updated_fields = HashMap::<String, ClassField>::new();
-
-            for (field, data) in fields {
-                let Some(updated_field) = local_env.get(field) else {
-                    return Err(format!(
-                            "An error occured when initalizing {}", field))
-                };
-                let mut new_data = data.clone();
-
-                new_data.value = updated_field.clone();
-
-                updated_fields.insert(field.to_string(), new_data);
+        ExpressionKind::ObjectCreation { module, class_name, arguments } => {
+            let mut vals = vec![];
+            for exp in arguments {
+                vals.push(eval_expression(&mut enviornment.clone(), exp, importing)?);
             }
 
-            if parent_object.is_some() {
-                let obj = parent_object.unwrap();
-
-                let Value::Object{ ref fields, .. } = obj else {
-                    return Err("Dev error: Non-object assigned to parent 
-                                eval:Expression:ObjectCreation".to_string())
-                };
-
-                for (field, data) in fields {
-                    let Some(updated_field) = local_env.get(field) else {
-                        return Err(format!(
-                                "An error occured when initalizing {}", field))
-                    };
-                    let mut new_data = data.clone();
-
-                    new_data.value = updated_field.clone();
-
-                    updated_fields.insert(field.to_string(), new_data);
-                }
-                let parent_field = ClassField {
-                    is_private: true,
-                    value: obj.clone(),
-                };
-
-                updated_fields.insert("super".to_string(),parent_field);
+            // A class from a module is looked up, along with its parent 
+            // classes, among the module's names
+            match module {
+                Some(module) => {
+                    let (_, members) = module_members(enviornment, module)?;
+                    create_object(members, class_name, vals, importing)
+                },
+                None => create_object(enviornment, class_name, vals, importing),
             }
-            
-
-            Ok(Value::Object{name: name.clone(), fields: updated_fields, 
-                             init: init.clone(), methods: methods.clone(),
-                             parent_class: parent_class.clone()})
         },
-        Expression::MethodCall { name, method, arguments  } => {
+        ExpressionKind::MethodCall { name, method, arguments  } => {
             if name == "super" {
 
                 let Some(res) = enviornment.get("super") else {
-                    return Err("super used with no parent".to_string());
+                    return Err(BrainstormError::name_error("super used with no parent"));
                 };
 
                 let Value::Object{methods, fields, ..} = res else {
-                    return Err("dev error, super not blacklisted".to_string());
+                    return Err(
+                        BrainstormError::type_error("dev error, super not blacklisted"));
                 };
 
                 for (name, data) in methods {
                     if name == method{
                         let method_args = data.arguments.clone();
                         if arguments.len() != method_args.len() {
-                            return Err(format!("Expected {} arguments, got {}", 
-                                            method_args.len(), arguments.len()))
+                            return Err(
+                                BrainstormError::arity_error(
+                                    format!("Expected {} arguments, got {}", 
+                                            method_args.len(), arguments.len())))
                         }
                         let mut local_env = HashMap::<String, Value>::new();
                         for (name, exp) 
@@ -845,7 +1306,8 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
                         for name in fields.keys() {
                             let Some(val) = enviornment.get(name) else {
                                 return Err(
-                                    "Dev error, undefined field".to_string());
+                                    BrainstormError::type_error(
+                                        "Dev error, undefined field"));
                             };
                             local_env.insert(name.to_string(), 
                                              val.clone());
@@ -853,32 +1315,45 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
 
                         insert_builtins(&mut local_env);
 
-                        for statement in &data.statements {
-                            eval_statement(&mut local_env, 
-                                            statement, importing)?;
-                        }
-
-                        if data.return_exp.is_some() {
-                            let exp = data.return_exp.clone().unwrap();
-                            return eval_expression(&mut local_env, 
-                                        &exp, importing)
-                        }
+                        let flow = eval_statements(&mut local_env, 
+                                                   &data.statements, importing)?;
 
-                        return Ok(Value::Null);
+                        return Ok(return_value(flow));
                     }
                 }
 
-                return Err(format!("{} is not defined or is private", method));
+                return Err(
+                    BrainstormError::name_error(
+                        format!("{} is not defined or is private", method)));
             }
 
             let Some(object) = enviornment.get(name) else {
-                return Err(format!("{} is not defined", name))
+                return Err(
+                    BrainstormError::name_error(format!("{} is not defined", name)))
             };
 
+            if let Value::Module { .. } = object {
+                let function = module_member(enviornment, name, method)?;
+                let mut vals = vec![];
+                for exp in arguments {
+                    vals.push(eval_expression(enviornment, exp, importing)?);
+                }
+                return call_function(enviornment, &function, vals, importing);
+            }
+
+            if let Value::EnumType { name: enum_name, variants } = object.clone() {
+                let mut vals = vec![];
+                for exp in arguments {
+                    vals.push(eval_expression(enviornment, exp, importing)?);
+                }
+                return make_variant(&enum_name, &variants, method, Some(vals));
+            }
+
             let Value::Object{ fields: object_fields, 
                                init: _init, methods: object_methods , ..} 
                                = object else {
-                return Err(format!("{} is not an object", name))
+                return Err(
+                    BrainstormError::type_error(format!("{} is not an object", name)))
             };
 
             for (m_name, method_data) in object_methods {
@@ -887,13 +1362,14 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
                     let obj_arguments = method_data.arguments.clone();
 
                     if obj_arguments.len() != arguments.len() {
-                        return Err(format!("Expected {} arguments, got {}", 
+                        return Err(
+                            BrainstormError::arity_error(
+                                format!("Expected {} arguments, got {}", 
                                             obj_arguments.len(),
-                                            arguments.len()));
+                                            arguments.len())));
                     }
 
                     let statements = method_data.statements.clone();
-                    let return_exp = method_data.return_exp.clone();
 
                     let mut local_env = HashMap::<String, Value>::new();
                     insert_builtins(&mut local_env);
@@ -913,21 +1389,15 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
                         local_env.insert(name.to_string(), data.clone().value);
                     }
 
-                    for statement in statements {
-                        eval_statement(&mut local_env, &statement, importing)?;
-                    }
+                    let flow = eval_statements(&mut local_env, &statements, importing)?;
 
-                    if return_exp.is_none() {
-                        return Ok(Value::Null)
-                    }
-
-                    let return_val = eval_expression(
-                        &mut local_env, &return_exp.unwrap(), importing)?;
-                    return Ok(return_val)
+                    return Ok(return_value(flow))
                 }
             };
 
-            Err(format!("{} is not a valid method or is private", method))
+            Err(
+                BrainstormError::name_error(
+                    format!("{} is not a valid method or is private", method)))
         }
         //_=> Err(format!("unhandled expression: {:?}", expression)),
     }
@@ -935,43 +1405,62 @@ fn eval_expression(enviornment: &mut HashMap<String, Value>,
 
 fn eval_expressions(enviornment: &mut HashMap<String, Value>, 
                     expressions: &Vec<Expression>, 
-                    importing: bool) -> Result<Vec<Value>, String> {
+                    importing: bool) -> Result<Vec<Value>, BrainstormError> {
         let mut vals = vec![];
 
         for expression in expressions {
-            match eval_expression(enviornment, expression, importing) {
-                Ok(v) => vals.push(v),
-                Err(e) => return Err(e),
-            }
+            vals.push(eval_expression(enviornment, expression, importing)?);
         }
 
         Ok(vals)
 }
 
+fn eval_condition(enviornment: &mut HashMap<String, Value>, 
+                  expression: &Expression, operator: &Operator, 
+                  importing: bool) -> Result<bool, BrainstormError> {
+    match eval_expression(enviornment, expression, importing)? {
+        Value::Bool{b} => Ok(b),
+        v => Err(
+            BrainstormError::type_error(
+                format!("Operator '{}' expects Bool operands, got {}", 
+                        operator, v.type_name())).at(&expression.span))
+    }
+}
+
 fn operate(operator: &Operator, lhs: &Value, rhs: &Value) 
-    -> Result<Value, String>{
+    -> Result<Value, BrainstormError>{
         match operator {
-            Operator::Plus => Ok(lhs + rhs),
-            Operator::Minus => Ok(lhs - rhs),
-            Operator::Times => Ok(lhs * rhs),
-            Operator::Divide => Ok(lhs / rhs),
-            Operator::LessThan => Ok(Value::Bool{b: lhs < rhs}),
-            Operator::GreaterThan => Ok(Value::Bool{b: lhs > rhs}),
+            Operator::Plus => lhs + rhs,
+            Operator::Minus => lhs - rhs,
+            Operator::Times => lhs * rhs,
+            Operator::Divide => lhs / rhs,
+            Operator::Modulo => lhs % rhs,
+            Operator::Power => lhs.pow(rhs),
+            Operator::FloorDivide => lhs.floor_div(rhs),
+            Operator::LessThan 
+            | Operator::GreaterThan 
+            | Operator::LessEqual 
+            | Operator::GreaterEqual => {
+                let Some(ordering) = lhs.compare(rhs) else {
+                    return Err(BrainstormError::type_error(
+                        format!("Cannot compare {} and {} with '{}'", 
+                                lhs.type_name(), rhs.type_name(), operator)))
+                };
+                let b = match operator {
+                    Operator::LessThan => ordering.is_lt(),
+                    Operator::GreaterThan => ordering.is_gt(),
+                    Operator::LessEqual => ordering.is_le(),
+                    _ => ordering.is_ge(),
+                };
+                Ok(Value::Bool{b})
+            },
             Operator::Equal => Ok(Value::Bool{b: lhs == rhs}),
             Operator::NotEqual => Ok(Value::Bool{b: lhs != rhs}),
+            Operator::In => Ok(Value::Bool{b: rhs.contains(lhs)?}),
+            // Logical operators short circuit, so they are evaluated by
+            // eval_expression before reaching here
+            Operator::And | Operator::Or | Operator::Not 
+                => Err(BrainstormError::type_error(
+                    format!("Cannot use '{}' here", operator))),
         }
 }
-              
-fn insert_builtins(env: &mut HashMap<String, Value>){
-    env.insert("println".to_string(), 
-        Value::Function{name: "println".to_string(), f: println_});
-    
-    env.insert("print".to_string(), 
-        Value::Function{name: "print".to_string(), f: print_});
-
-    env.insert("range".to_string(), 
-        Value::Function{name: "range".to_string(), f: range});
-
-    env.insert("range_step".to_string(), 
-        Value::Function{name: "range_step".to_string(), f: range_step});
-}
\ No newline at end of file
@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use crate::ast::Program;
+use crate::error::BrainstormError;
+use crate::eval::parse_program;
+use crate::read_file;
+use crate::source::{SourceFile, Span};
+use crate::value::Value;
+
+thread_local! {
+    // The directories searched for imports that are not found next to the
+    // importing file, in order
+    static SEARCH_PATH: RefCell<Vec<PathBuf>> = const { RefCell::new(vec![]) };
+    // The names defined by each module evaluated so far, by canonical path
+    static MODULES: RefCell<HashMap<PathBuf, Rc<HashMap<String, Value>>>> =
+        RefCell::new(HashMap::new());
+    // The canonical and shown paths of the files being evaluated, starting
+    // with the program and ending with the innermost import
+    static LOADING: RefCell<Vec<(PathBuf, PathBuf)>> = const { RefCell::new(vec![]) };
+    // The canonical path of the module each imported name was bound from,
+    // by the file importing it
+    static BOUND: RefCell<HashMap<String, HashMap<String, PathBuf>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Sets up where imports are searched for when running `program`. After
+/// the directory of the importing file, these are searched in order:
+///
+/// 1. the directories given with `-I <dir>`, in the order given
+/// 2. the `lib/` directory next to `program`
+/// 3. the directories listed in `BRNSTM_PATH`, separated by `:`
+/// 4. the `BRNSTM_LIB` directory
+pub fn set_search_path(program: &Path, include_dirs: Vec<PathBuf>) {
+    let mut dirs = include_dirs;
+    dirs.push(program.parent().unwrap_or(Path::new("")).join("lib"));
+    if let Some(path) = env::var_os("BRNSTM_PATH") {
+        dirs.extend(env::split_paths(&path).filter(|dir| !dir.as_os_str().is_empty()));
+    }
+    if let Some(lib) = env::var_os("BRNSTM_LIB") {
+        dirs.push(PathBuf::from(lib));
+    }
+
+    SEARCH_PATH.with(|search_path| *search_path.borrow_mut() = dirs);
+}
+
+/// The canonical path of the module an import at `origin` names, and the
+/// names it defines. Each file is run with `evaluate` only the first time it
+/// is imported, and importing a file that is still being evaluated is an
+/// import cycle.
+pub fn import_module<F>(path: &str, origin: &Span, evaluate: F)
+    -> Result<(PathBuf, Rc<HashMap<String, Value>>), BrainstormError>
+    where F: FnOnce(&Program) -> Result<HashMap<String, Value>, BrainstormError> {
+    let target = find_module(path, origin)?;
+    let canonical = canonicalize(&target);
+    let cached = MODULES.with(|modules| modules.borrow().get(&canonical).cloned());
+    if let Some(members) = cached {
+        return Ok((canonical, members))
+    }
+
+    LOADING.with(|loading| {
+        let mut loading = loading.borrow_mut();
+        if loading.is_empty() {
+            let program = PathBuf::from(&*origin.file);
+            loading.push((canonicalize(&program), program));
+        }
+        match loading.iter().position(|(file, _)| *file == canonical) {
+            Some(start) => {
+                let cycle: Vec<String> = loading[start..].iter()
+                    .map(|(_, shown)| format!("{} -> ", shown.display()))
+                    .collect();
+                Err(BrainstormError::import_error(
+                    format!("Import cycle: {}{}", cycle.concat(), target.display())))
+            },
+            None => {
+                loading.push((canonical.clone(), target.clone()));
+                Ok(())
+            },
+        }
+    })?;
+
+    let members = load_module(&target).and_then(|ast| evaluate(&ast));
+    LOADING.with(|loading| loading.borrow_mut().pop());
+    let members = Rc::new(members?);
+    MODULES.with(|modules| {
+        modules.borrow_mut().insert(canonical.clone(), members.clone())
+    });
+    Ok((canonical, members))
+}
+
+/// Whether the import at `origin` has already bound `name` to the member
+/// of the module at `module`.
+pub fn is_bound_from(origin: &Span, name: &str, module: &Path) -> bool {
+    BOUND.with(|bound| {
+        bound.borrow().get(&*origin.file)
+            .and_then(|names| names.get(name))
+            .is_some_and(|bound_from| bound_from == module)
+    })
+}
+
+/// Records that the file containing the import at `origin` bound `name` to
+/// the member of the module at `module`.
+pub fn record_binding(origin: &Span, name: &str, module: &Path) {
+    BOUND.with(|bound| {
+        bound.borrow_mut().entry(origin.file.to_string()).or_default()
+            .insert(name.to_string(), module.to_path_buf())
+    });
+}
+
+/// Reads and parses the module at `target`.
+fn load_module(target: &Path) -> Result<Program, BrainstormError> {
+    let Ok(external_code) = read_file(target.to_path_buf()) else {
+        return Err(
+            BrainstormError::import_error(
+                format!("Error opening file at {}", target.display())))
+    };
+
+    let source = SourceFile::new(&target.to_string_lossy(), external_code);
+    parse_program(&source)
+}
+
+/// Finds the file an import names, searching next to the file containing
+/// the import at `origin` and then along the search path.
+fn find_module(path: &str, origin: &Span) -> Result<PathBuf, BrainstormError> {
+    let importer = Path::new(&*origin.file);
+    let mut candidates = vec![importer.parent().unwrap_or(Path::new("")).to_path_buf()];
+    // Absolute paths are only looked for where they point
+    if Path::new(path).is_relative() {
+        SEARCH_PATH.with(|search_path| {
+            candidates.extend(search_path.borrow().iter().cloned())
+        });
+    }
+
+    let mut tried: Vec<PathBuf> = vec![];
+    for dir in candidates {
+        let target = normalize(&dir.join(path));
+        if tried.contains(&target) {
+            continue;
+        }
+        if target.is_file() {
+            return Ok(target)
+        }
+        tried.push(target);
+    }
+
+    let tried: Vec<String> = tried.iter()
+        .map(|target| format!("\n    {}", target.display()))
+        .collect();
+    Err(BrainstormError::import_error(
+        format!("Cannot find \"{}\", tried:{}", path, tried.concat())))
+}
+
+/// The path `path` resolves to, so that a module reached through different
+/// paths is still the same module.
+fn canonicalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| normalize(path))
+}
+
+/// Removes the `.` and `..` components of `path` where it can without
+/// looking at the file system, so that errors show short paths.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                },
+                Some(Component::RootDir) => {},
+                _ => normalized.push(".."),
+            },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -1,124 +1,80 @@
 use std::env::args;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Error};
+use std::fs;
+use std::io::Error;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process;
 
 mod ast; 
+mod builtins;
+mod check;
+mod collections;
 mod eval;
 mod constants;
+mod error;
+mod imports;
+mod source;
+mod types;
 mod value;
 
-use crate::value::Value;
+use crate::builtins::insert_builtins;
+use crate::error::BrainstormError;
+use crate::source::SourceFile;
 
 #[macro_use]
 extern crate lalrpop_util; 
-lalrpop_mod!(pub parser);
+lalrpop_mod!(#[allow(clippy::all)] pub parser);
 
 
 fn main() {
     let args: Vec<String> = args().collect();
-    if args.len() != 2 {
-        println!("Usage: {} <filename>", args[0]);
-        return;
-    }
-
-    let file = match read_file((&args[1]).into()) {
-        Ok(file) => file,
-        Err(e) => panic!("{}", e)
+    let Some((path, include_dirs)) = parse_args(&args[1..]) else {
+        eprintln!("Usage: {} [-I <dir>]... <filename>", args[0]);
+        process::exit(1);
     };
 
-    let mut enviornment = HashMap::new();
-    enviornment.insert("println".to_string(), 
-        Value::Function{name: "println".to_string(), f: println_});
-    
-    enviornment.insert("print".to_string(), 
-        Value::Function{name: "print".to_string(), f: print_});
-
-    enviornment.insert("range".to_string(), 
-        Value::Function{name: "range".to_string(), f: range});
-
-    enviornment.insert("range_step".to_string(), 
-        Value::Function{name: "range_step".to_string(), f: range_step});
-        
-    let ast = parser::ProgramParser::new().parse(&file).unwrap();
-    
-    let result = eval::eval_program(&mut enviornment, &ast, false);
-
-    match &result {
-        Ok(()) => (),
-        Err(s) => {
-            //println!("\nError: {s}\n\nAST:\n{:?}\n", ast);
-            println!("\nError: {s}\n");
-        },
+    imports::set_search_path(Path::new(&path), include_dirs);
+    if let Err(e) = run(&path) {
+        eprintln!("\n{e}\n");
+        process::exit(e.exit_code());
     }
 }
 
-pub fn read_file(path: PathBuf) -> Result<String, Error> {
-    let f = match File::open(path) {
-        Ok(f) => f,
-        Err(e) => return Err(e)
-    };
-
-    let lines = BufReader::new(f).lines();
-    let mut file = String::new();
-
-    for s in lines{
-        match s {
-            Ok(s) => file.push_str(&s),
-            Err(e) => return Err(e)
+/// Splits the command line into the program to run and the directories
+/// given with `-I` to search for imports, or `None` if it is malformed.
+fn parse_args(args: &[String]) -> Option<(String, Vec<PathBuf>)> {
+    let mut path = None;
+    let mut include_dirs = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("-I") {
+            Some("") => include_dirs.push(PathBuf::from(args.next()?)),
+            Some(dir) => include_dirs.push(PathBuf::from(dir)),
+            None if path.is_none() => path = Some(arg.clone()),
+            None => return None,
         }
     }
 
-    Ok(file)
+    path.map(|path| (path, include_dirs))
 }
 
+fn run(path: &str) -> Result<(), BrainstormError> {
+    let file = match read_file(path.into()) {
+        Ok(file) => file,
+        Err(e) => return Err(
+            BrainstormError::io_error(format!("Error opening file at {path}: {e}")))
+    };
 
+    let mut enviornment = HashMap::new();
+    insert_builtins(&mut enviornment);
 
-#[allow(clippy::unnecessary_wraps)]
-pub fn println_(args: Vec<Value>) -> Result<Value, String> {
-    for arg in args {
-        print!("{}", arg);
-    }
-    println!();
-    Ok(Value::Null)
-}
-
-pub fn print_(args: Vec<Value>) -> Result<Value, String> {
-    for arg in args {
-        print!("{}", arg);
-    }
-    Ok(Value::Null)
-}
-
-#[allow(clippy::missing_panics_doc)]
-pub fn range_step(args: Vec<Value>) -> Result<Value, String> {
-    let mut vals = vec![];
-
-    let start = args[0].clone();
-    let end = args[1].clone();
-    let step = args[2].clone();
-
-    let Value::Int{v: s} = start 
-        else { return Err("Invalid Type".to_string())};
-    
-    let Value::Int{v: e} = end 
-        else { return Err("Invalid Type".to_string())};
-
-    let Value::Int{v: st} = step 
-        else { return Err("Invalid Type".to_string())};
+    let source = SourceFile::new(path, file);
+    let ast = eval::parse_program(&source)?;
     
-    for x in (s..e).step_by(st.try_into().unwrap()) {
-        vals.push(Value::Int{v: x});
-    }
-
-    Ok(Value::List{e: vals})
+    eval::eval_program(&mut enviornment, &ast, false)
 }
 
-pub fn range(args: Vec<Value>) -> Result<Value, String> {
-    let mut vals = args;
-    vals.push(Value::Int{v: 1});
-    range_step(vals)
+pub fn read_file(path: PathBuf) -> Result<String, Error> {
+    fs::read_to_string(path)
 }
-
-
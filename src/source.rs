@@ -0,0 +1,71 @@
+use std::fmt;
+use std::rc::Rc;
+
+/// Position of a node in a source file. Lines and columns start at 1.
+#[derive(Clone)]
+pub struct Span {
+    pub file: Rc<str>,
+    pub line: usize,
+    pub column: usize,
+    // The text of the file as it was parsed, for showing the line in errors
+    text: Rc<str>,
+}
+
+impl PartialEq for Span {
+    fn eq(&self, other: &Self) -> bool {
+        self.file == other.file && self.line == other.line && self.column == other.column
+    }
+}
+
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// A file handed to the parser, used to turn byte offsets into spans.
+pub struct SourceFile {
+    pub path: Rc<str>,
+    pub text: Rc<str>,
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(path: &str, text: String) -> Self {
+        let mut line_starts = vec![0];
+        for (idx, c) in text.char_indices() {
+            if c == '\n' {
+                line_starts.push(idx + 1);
+            }
+        }
+
+        SourceFile{path: Rc::from(path), text: Rc::from(text), line_starts}
+    }
+
+    pub fn span(&self, offset: usize) -> Span {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+        let line_start = self.line_starts[line];
+        let column = self.text[line_start..offset].chars().count();
+
+        Span{file: self.path.clone(), line: line + 1, column: column + 1, 
+             text: self.text.clone()}
+    }
+}
+
+/// Renders the line a span points at, as it was when the file was parsed,
+/// with a caret under its column.
+pub fn snippet(span: &Span) -> Option<String> {
+    let line = span.text.lines().nth(span.line - 1)?;
+
+    let number = span.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let indent: String = line.chars()
+        .take(span.column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    Some(format!("{gutter} |\n{number} | {line}\n{gutter} | {indent}^"))
+}
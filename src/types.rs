@@ -0,0 +1,1149 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::ast::{Expression, ExpressionKind, ImportedNames, ListItem, Operator, 
+                 Pattern, PatternKind, Program, Signature, Statement, StatementKind, 
+                 TypeAnnotation, UserClass};
+use crate::builtins::BUILTINS;
+use crate::error::BrainstormError;
+use crate::eval::check_arity;
+
+/// A type known before the program runs. Values whose type cannot be worked
+/// out are `Any`, which fits everywhere, so unannotated code stays dynamic.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Type {
+    Any,
+    Null,
+    Int,
+    Float,
+    Str,
+    Bool,
+    Char,
+    List(Box<Type>),
+    Map,
+    Set,
+    Function,
+    // A class or an enum
+    Named(String),
+}
+
+impl Type {
+    /// The type of the items iterating over a value of this type produces,
+    /// or `None` if it cannot be iterated over.
+    fn element(&self) -> Option<Type> {
+        match self {
+            Type::List(element) => Some((**element).clone()),
+            Type::Str => Some(Type::Char),
+            Type::Any | Type::Map | Type::Set => Some(Type::Any),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "any"),
+            Type::Null => write!(f, "null"),
+            Type::Int => write!(f, "int"),
+            Type::Float => write!(f, "float"),
+            Type::Str => write!(f, "str"),
+            Type::Bool => write!(f, "bool"),
+            Type::Char => write!(f, "char"),
+            Type::List(element) if **element == Type::Any => write!(f, "list"),
+            Type::List(element) => write!(f, "list[{}]", element),
+            Type::Map => write!(f, "map"),
+            Type::Set => write!(f, "set"),
+            Type::Function => write!(f, "fn"),
+            Type::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The most precise type covering both `a` and `b`.
+fn join(a: &Type, b: &Type) -> Type {
+    match (a, b) {
+        (Type::List(a), Type::List(b)) => Type::List(Box::new(join(a, b))),
+        (a, b) if a == b => a.clone(),
+        _ => Type::Any,
+    }
+}
+
+#[derive(Clone)]
+struct FunctionType {
+    parameters: Vec<(String, Type)>,
+    // Inferred from the body when there is no annotation
+    returns: Option<Type>,
+}
+
+struct ClassType {
+    parent: Option<String>,
+    fields: HashMap<String, Type>,
+    init: Vec<(String, Type)>,
+    methods: HashMap<String, FunctionType>,
+}
+
+/// What is known about the variables at a statement, and about the
+/// function or method it is in.
+#[derive(Clone,Default)]
+struct Scope {
+    // Annotated variables, which every value assigned has to fit
+    declared: HashMap<String, Type>,
+    // The types of the values last assigned to unannotated variables
+    inferred: HashMap<String, Type>,
+    function: Option<(String, Type)>,
+    // Collects what the function returns while its return type is inferred
+    returns: Option<Rc<RefCell<Vec<Type>>>>,
+    class: Option<String>,
+}
+
+impl Scope {
+    /// Makes `name` a variable whose type is unknown.
+    fn forget(&mut self, name: &str) {
+        self.declared.remove(name);
+        self.inferred.insert(name.to_string(), Type::Any);
+    }
+
+    /// What is known after running either the code that led to `self` or
+    /// the code that led to `other`. A variable only one of them assigns
+    /// may hold anything.
+    fn merge(&self, other: &Scope) -> Scope {
+        Scope{declared: merge_types(&self.declared, &other.declared),
+              inferred: merge_types(&self.inferred, &other.inferred),
+              ..self.clone()}
+    }
+
+    /// Makes the fields of a class variables of an initializer or method,
+    /// where they can be read and assigned without `this.`. Like at run
+    /// time, they take the place of parameters of the same name.
+    fn add_fields(&mut self, fields: &HashMap<String, Type>) {
+        for (name, declared) in fields {
+            match declared {
+                Type::Any => self.forget(name),
+                declared => {
+                    self.inferred.remove(name);
+                    self.declared.insert(name.clone(), declared.clone());
+                },
+            }
+        }
+    }
+
+    fn same_types(&self, other: &Scope) -> bool {
+        self.declared == other.declared && self.inferred == other.inferred
+    }
+}
+
+fn merge_types(a: &HashMap<String, Type>, b: &HashMap<String, Type>)
+    -> HashMap<String, Type> {
+    let mut merged = HashMap::new();
+    for (name, a_type) in a {
+        let joined = b.get(name).map_or(Type::Any, |b_type| join(a_type, b_type));
+        merged.insert(name.clone(), joined);
+    }
+    for name in b.keys() {
+        merged.entry(name.clone()).or_insert(Type::Any);
+    }
+    merged
+}
+
+/// Checks the program before it runs. Annotated code has to fit its
+/// annotations, and the types of unannotated variables, function results
+/// and list items are inferred where they can be, to report operations
+/// that are bound to fail, like indexing an int.
+pub fn check_types(Program::Body{statements}: &Program)
+    -> Result<(), BrainstormError> {
+    let mut checker = Checker::default();
+    checker.declare(statements)?;
+    checker.check_statements(statements, &mut Scope::default())
+}
+
+#[derive(Default)]
+struct Checker<'a> {
+    functions: HashMap<String, FunctionType>,
+    bodies: HashMap<String, &'a [Statement]>,
+    // Every function name the program defines, including those defined
+    // more than once, which replace builtins of the same name
+    defined: HashSet<String>,
+    classes: HashMap<String, ClassType>,
+    // Classes defined more than once, which are known by name only
+    redefined_classes: HashSet<String>,
+    enums: Vec<String>,
+    // Holds `None` while the function's return type is being inferred
+    inferred_returns: RefCell<HashMap<String, Option<Type>>>,
+}
+
+impl<'a> Checker<'a> {
+    /// Records every function, class and enum the program defines, so that
+    /// they can be used before their definition is reached.
+    fn declare(&mut self, statements: &'a [Statement]) -> Result<(), BrainstormError> {
+        let mut functions = vec![];
+        let mut classes = vec![];
+        collect_definitions(statements, &mut functions, &mut classes, &mut self.enums);
+
+        // Like functions, a class defined more than once can create objects
+        // of either definition, so its fields, methods and bodies are not
+        // checked
+        let mut class_definitions: HashMap<&str, usize> = HashMap::new();
+        for class in &classes {
+            *class_definitions.entry(&class.name).or_default() += 1;
+        }
+        classes.retain(|class| {
+            let unique = class_definitions[class.name.as_str()] == 1;
+            if !unique {
+                self.redefined_classes.insert(class.name.clone());
+            }
+            unique
+        });
+
+        for class in &classes {
+            self.classes.insert(class.name.clone(), ClassType{
+                parent: class.parent.clone(), fields: HashMap::new(),
+                init: vec![], methods: HashMap::new(),
+            });
+        }
+
+        for class in classes {
+            let mut fields = HashMap::new();
+            for (name, field) in &class.fields {
+                fields.insert(name.clone(), self.resolve_optional(&field.annotation)?);
+            }
+            let init = match &class.init.arguments {
+                Some(arguments) => {
+                    let signature = Signature{
+                        parameter_types: class.init.parameter_types.clone(),
+                        return_type: None,
+                    };
+                    self.signature(arguments, &signature)?.parameters
+                },
+                None => vec![],
+            };
+            let mut methods = HashMap::new();
+            for (name, method) in &class.methods {
+                methods.insert(name.clone(),
+                               self.signature(&method.arguments, &method.signature)?);
+            }
+
+            let class_type = self.classes.get_mut(&class.name)
+                .expect("classes are declared before their members");
+            class_type.fields = fields;
+            class_type.init = init;
+            class_type.methods = methods;
+        }
+
+        // A name defined more than once, in different functions or branches,
+        // can call any of its definitions, so its calls are left unchecked
+        // and their results are not inferred from any one body
+        let mut definitions: HashMap<&str, usize> = HashMap::new();
+        for (name, ..) in &functions {
+            *definitions.entry(name).or_default() += 1;
+        }
+        for (name, arguments, signature, statements) in functions {
+            let function = self.signature(arguments, signature)?;
+            self.defined.insert(name.to_string());
+            if definitions[name] == 1 {
+                self.functions.insert(name.to_string(), function);
+                self.bodies.insert(name.to_string(), statements);
+            }
+        }
+        Ok(())
+    }
+
+    fn signature(&self, arguments: &[String], signature: &Signature)
+        -> Result<FunctionType, BrainstormError> {
+        let mut parameters = vec![];
+        for (idx, name) in arguments.iter().enumerate() {
+            let annotation = signature.parameter_types.get(idx).cloned().flatten();
+            parameters.push((name.clone(), self.resolve_optional(&annotation)?));
+        }
+
+        let returns = match &signature.return_type {
+            Some(annotation) => Some(self.resolve(annotation)?),
+            None => None,
+        };
+        Ok(FunctionType{parameters, returns})
+    }
+
+    fn resolve_optional(&self, annotation: &Option<TypeAnnotation>)
+        -> Result<Type, BrainstormError> {
+        match annotation {
+            Some(annotation) => self.resolve(annotation),
+            None => Ok(Type::Any),
+        }
+    }
+
+    fn resolve(&self, annotation: &TypeAnnotation) -> Result<Type, BrainstormError> {
+        let name = annotation.name.as_str();
+        let resolved = match (name, &annotation.element) {
+            ("list", Some(element)) => Type::List(Box::new(self.resolve(element)?)),
+            (_, Some(_)) => return Err(
+                BrainstormError::type_error(
+                    format!("'{}' does not take an element type", name))
+                    .at(&annotation.span)),
+            ("any", None) => Type::Any,
+            ("null", None) => Type::Null,
+            ("int", None) => Type::Int,
+            ("float", None) => Type::Float,
+            ("str", None) => Type::Str,
+            ("bool", None) => Type::Bool,
+            ("char", None) => Type::Char,
+            ("list", None) => Type::List(Box::new(Type::Any)),
+            ("map", None) => Type::Map,
+            ("set", None) => Type::Set,
+            ("fn", None) => Type::Function,
+            (name, None) if self.classes.contains_key(name)
+                || self.redefined_classes.contains(name)
+                || self.enums.iter().any(|e| e == name) => Type::Named(name.to_string()),
+            (name, None) => return Err(
+                BrainstormError::name_error(format!("Unknown type '{}'", name))
+                    .at(&annotation.span)),
+        };
+        Ok(resolved)
+    }
+
+    /// Whether a value of type `actual` can be used where `expected` is
+    /// declared. Ints widen to floats and objects fit their parent classes.
+    fn fits(&self, actual: &Type, expected: &Type) -> bool {
+        match (actual, expected) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Int, Type::Float) => true,
+            (Type::List(actual), Type::List(expected)) => self.fits(actual, expected),
+            (Type::Named(actual), Type::Named(expected)) => {
+                let mut class = Some(actual.clone());
+                while let Some(name) = class {
+                    if name == *expected {
+                        return true;
+                    }
+                    class = self.classes.get(&name).and_then(|c| c.parent.clone());
+                }
+                false
+            },
+            (actual, expected) => actual == expected,
+        }
+    }
+
+    /// Checks that `expression` produces a value that fits `expected`. List
+    /// literals are checked item by item, to point at the item that does not.
+    fn expect(&self, expression: &Expression, expected: &Type, context: &str,
+              scope: &mut Scope) -> Result<(), BrainstormError> {
+        if let (ExpressionKind::List { items }, Type::List(element))
+            = (&expression.kind, expected) {
+            for ListItem { expression, is_spread, is_pack } in items {
+                if *is_spread || *is_pack {
+                    self.expect(expression, expected, context, scope)?;
+                } else {
+                    self.expect(expression, element, context, scope)?;
+                }
+            }
+            return Ok(());
+        }
+
+        let actual = self.type_of(expression, scope)?;
+        if !self.fits(&actual, expected) {
+            return Err(
+                BrainstormError::type_error(
+                    format!("Expected {} {}, got {}", expected, context, actual))
+                    .at(&expression.span))
+        }
+        Ok(())
+    }
+
+    fn check_statements(&self, statements: &[Statement], scope: &mut Scope)
+        -> Result<(), BrainstormError> {
+        for statement in statements {
+            self.check_statement(statement, scope).map_err(|e| e.at(&statement.span))?;
+        }
+        Ok(())
+    }
+
+    fn check_statement(&self, statement: &Statement, scope: &mut Scope)
+        -> Result<(), BrainstormError> {
+        match &statement.kind {
+            StatementKind::Import { names: ImportedNames::Module { name }, .. }
+                => scope.forget(name),
+            StatementKind::Import { names: ImportedNames::Listed { names }, .. } => {
+                for name in names {
+                    scope.forget(name);
+                }
+            },
+            StatementKind::Import { .. }
+            | StatementKind::EnumDefinition { .. }
+            | StatementKind::Break { .. }
+            | StatementKind::Continue { .. } => {},
+            StatementKind::Expression { expression } => {
+                self.type_of(expression, scope)?;
+            },
+            StatementKind::Declaration { name, annotation, rhs } => {
+                let declared = self.resolve(annotation)?;
+                self.expect(rhs, &declared, &format!("for '{}'", name), scope)?;
+                scope.inferred.remove(name);
+                scope.declared.insert(name.clone(), declared);
+            },
+            StatementKind::Assignment { lhs, rhs }
+                => self.check_assignment(lhs, rhs, scope)?,
+            StatementKind::OperatorAssignment { name, operator, rhs } => {
+                let rhs_type = self.type_of(rhs, scope)?;
+                let current = self.variable_type(name, scope);
+                let result = self.operation(operator, &current, &rhs_type)?;
+                self.assign(name, result, scope)?;
+            },
+            StatementKind::If { params } => {
+                self.type_of(&params.condition, scope)?;
+                let mut after = scope.clone();
+                self.check_statements(&params.statements, &mut after)?;
+
+                let (elif_conditions, elif_statements) = &params.elif_data;
+                let elifs = elif_conditions.iter().zip(elif_statements);
+                for (condition, statements) in elifs {
+                    let mut branch = scope.clone();
+                    self.type_of(condition, &mut branch)?;
+                    self.check_statements(statements, &mut branch)?;
+                    after = after.merge(&branch);
+                }
+
+                // Without an `else`, none of the branches may run
+                let mut branch = scope.clone();
+                if let Some(else_statements) = &params.else_statements {
+                    self.check_statements(else_statements, &mut branch)?;
+                }
+                *scope = after.merge(&branch);
+            },
+            StatementKind::While { condition, statements, .. } => {
+                self.check_loop(scope, |body| {
+                    self.type_of(condition, body)?;
+                    self.check_statements(statements, body)
+                })?;
+            },
+            StatementKind::For { params } => {
+                let iterated = self.type_of(&params.iterate_expression, scope)?;
+                let element = self.iterate(&iterated)
+                    .map_err(|e| e.at(&params.iterate_expression.span))?;
+                self.check_loop(scope, |body| {
+                    body.declared.remove(&params.loop_var);
+                    body.inferred.insert(params.loop_var.clone(), element.clone());
+                    self.check_statements(&params.statements, body)
+                })?;
+            },
+            StatementKind::Match { subject, arms } => {
+                self.type_of(subject, scope)?;
+                let mut after = scope.clone();
+                for arm in arms {
+                    let mut branch = scope.clone();
+                    for name in pattern_bindings(&arm.pattern) {
+                        branch.forget(&name);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.type_of(guard, &mut branch)?;
+                    }
+                    self.check_statements(&arm.statements, &mut branch)?;
+                    after = after.merge(&branch);
+                }
+                *scope = after;
+            },
+            StatementKind::Throw { value } => {
+                self.type_of(value, scope)?;
+            },
+            StatementKind::Try { statements, catch, finally } => {
+                let mut after = scope.clone();
+                self.check_statements(statements, &mut after)?;
+                // The `try` block can be cut short by an error at any point
+                let interrupted = scope.merge(&after);
+
+                if let Some(catch) = catch {
+                    let mut caught = interrupted.clone();
+                    caught.forget(&catch.name);
+                    self.check_statements(&catch.statements, &mut caught)?;
+                    after = after.merge(&caught);
+                }
+                if let Some(finally) = finally {
+                    after = after.merge(&interrupted);
+                    self.check_statements(finally, &mut after)?;
+                }
+                *scope = after;
+            },
+            StatementKind::Public { statement } 
+                => self.check_statement(statement, scope)?,
+            StatementKind::FunctionDefinition { name, arguments, 
+                                                signature, statements } => {
+                let function = self.signature(arguments, signature)?;
+                let mut body = function_scope(&format!("'{}'", name), &function, None);
+                self.check_statements(statements, &mut body)?;
+            },
+            StatementKind::Return { expression } => {
+                let returned = match expression {
+                    Some(expression) => self.type_of(expression, scope)?,
+                    None => Type::Null,
+                };
+                if let Some(returns) = &scope.returns {
+                    returns.borrow_mut().push(returned);
+                }
+
+                let Some((name, returns)) = scope.function.clone() else { return Ok(()) };
+                let context = format!("as the return value of {}", name);
+                match expression {
+                    Some(expression)
+                        => self.expect(expression, &returns, &context, scope)?,
+                    None if !self.fits(&Type::Null, &returns) => return Err(
+                        BrainstormError::type_error(
+                            format!("Expected {} {}, got null", returns, context))),
+                    None => {},
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Checks a loop, whose body runs any number of times. The body is gone
+    /// over until what is known at its start stops changing, and errors are
+    /// only reported on the last pass, once the types are settled.
+    fn check_loop(&self, scope: &mut Scope,
+                  check_body: impl Fn(&mut Scope) -> Result<(), BrainstormError>)
+        -> Result<(), BrainstormError> {
+        let mut start = scope.clone();
+        loop {
+            let mut body = start.clone();
+            let _ = check_body(&mut body);
+            let merged = start.merge(&body);
+            if merged.same_types(&start) {
+                break;
+            }
+            start = merged;
+        }
+
+        let mut body = start.clone();
+        check_body(&mut body)?;
+        *scope = start.merge(&body);
+        Ok(())
+    }
+
+    /// Records that `name` now holds a value of type `assigned`, which has
+    /// to fit the variable's annotation if it has one.
+    fn assign(&self, name: &str, assigned: Type, scope: &mut Scope)
+        -> Result<(), BrainstormError> {
+        match scope.declared.get(name) {
+            Some(declared) if !self.fits(&assigned, declared) => return Err(
+                BrainstormError::type_error(
+                    format!("Expected {} for '{}', got {}", declared, name, assigned))),
+            Some(_) => {},
+            None => {
+                scope.inferred.insert(name.to_string(), assigned);
+            },
+        }
+        Ok(())
+    }
+
+    fn check_assignment(&self, lhs: &Expression, rhs: &Expression, scope: &mut Scope)
+        -> Result<(), BrainstormError> {
+        match &lhs.kind {
+            ExpressionKind::Identifier { name } => match scope.declared.get(name) {
+                Some(declared) => {
+                    let declared = declared.clone();
+                    self.expect(rhs, &declared, &format!("for '{}'", name), scope)
+                },
+                None => {
+                    let assigned = self.type_of(rhs, scope)?;
+                    scope.inferred.insert(name.clone(), assigned);
+                    Ok(())
+                },
+            },
+            ExpressionKind::FieldAccess { name, field } => {
+                let object = self.variable_type(name, scope);
+                match self.field_type(&object, field) {
+                    Some(declared) => {
+                        let context = format!("for field '{}' of '{}'", field, object);
+                        self.expect(rhs, &declared, &context, scope)
+                    },
+                    None => self.type_of(rhs, scope).map(|_| ()),
+                }
+            },
+            ExpressionKind::Index { name, idx_exp } => {
+                self.type_of(idx_exp, scope)?;
+                if let Some(Type::List(element)) = scope.declared.get(name) {
+                    let element = (**element).clone();
+                    return self.expect(rhs, &element, &format!("in '{}'", name), scope)
+                }
+
+                let assigned = self.type_of(rhs, scope)?;
+                if let Some(Type::List(element)) = scope.inferred.get(name) {
+                    let widened = Type::List(Box::new(join(element, &assigned)));
+                    scope.inferred.insert(name.clone(), widened);
+                }
+                Ok(())
+            },
+            ExpressionKind::List { items } => {
+                let element = match self.type_of(rhs, scope)? {
+                    Type::List(element) => *element,
+                    _ => Type::Any,
+                };
+                for ListItem { expression, is_pack, .. } in items {
+                    let ExpressionKind::Identifier { name } = &expression.kind else {
+                        continue
+                    };
+                    let item = match is_pack {
+                        true => Type::List(Box::new(element.clone())),
+                        false => element.clone(),
+                    };
+                    self.assign(name, item, scope).map_err(|e| e.at(&expression.span))?;
+                }
+                Ok(())
+            },
+            _ => self.type_of(rhs, scope).map(|_| ()),
+        }
+    }
+
+    fn check_class(&self, class: &UserClass) -> Result<(), BrainstormError> {
+        // Classes defined more than once, or inside expressions as in
+        // `x = class Q { ... };`, are not declared, so their bodies are left
+        // unchecked
+        let Some(class_type) = self.classes.get(&class.name) else { return Ok(()) };
+
+        if let Some(statements) = &class.init.statements {
+            let init = FunctionType{parameters: class_type.init.clone(), returns: None};
+            let mut body = function_scope(&format!("'{}.init'", class.name), &init,
+                                          Some(&class.name));
+            body.add_fields(&class_type.fields);
+            self.check_statements(statements, &mut body)?;
+        }
+        for (name, method) in &class.methods {
+            let Some(method_type) = class_type.methods.get(name) else { continue };
+            let mut body = function_scope(&format!("'{}.{}'", class.name, name),
+                                          method_type, Some(&class.name));
+            body.add_fields(&class_type.fields);
+            self.check_statements(&method.statements, &mut body)?;
+        }
+        Ok(())
+    }
+
+    /// What calling the function `name` returns: its annotation, or else
+    /// the types of the values its body can return.
+    fn return_type(&self, name: &str, function: &FunctionType) -> Type {
+        if let Some(returns) = &function.returns {
+            return returns.clone();
+        }
+        if let Some(inferred) = self.inferred_returns.borrow().get(name) {
+            // A recursive call made while the type is being inferred
+            return inferred.clone().unwrap_or(Type::Any);
+        }
+        self.inferred_returns.borrow_mut().insert(name.to_string(), None);
+
+        let statements = self.bodies[name];
+        let returns = Rc::new(RefCell::new(vec![]));
+        let mut body = function_scope(&format!("'{}'", name), function, None);
+        body.returns = Some(returns.clone());
+
+        // Errors in the body are reported when its definition is checked
+        let inferred = match self.check_statements(statements, &mut body) {
+            Ok(()) => {
+                let mut returned = returns.take();
+                if !always_returns(statements) {
+                    returned.push(Type::Null);
+                }
+                returned.iter().cloned().reduce(|a, b| join(&a, &b)).unwrap_or(Type::Any)
+            },
+            Err(_) => Type::Any,
+        };
+        let mut inferred_returns = self.inferred_returns.borrow_mut();
+        inferred_returns.insert(name.to_string(), Some(inferred.clone()));
+        inferred
+    }
+
+    /// Checks the arguments of a call to a function with a known signature.
+    fn check_call(&self, name: &str, parameters: &[(String, Type)],
+                  arguments: &[Expression], scope: &mut Scope)
+        -> Result<(), BrainstormError> {
+        check_arity(parameters.len(), arguments.len())?;
+
+        for ((parameter, expected), argument) in parameters.iter().zip(arguments) {
+            let context = format!("for argument '{}' of {}", parameter, name);
+            self.expect(argument, expected, &context, scope)?;
+        }
+        Ok(())
+    }
+
+    /// Checks a call to the builtin `name` and works out what it returns.
+    /// Only the builtins that fail for arguments of the wrong type are
+    /// checked.
+    fn check_builtin(&self, name: &str, arguments: &[Expression], scope: &mut Scope)
+        -> Result<Type, BrainstormError> {
+        // A parameter without a type takes anything that can be iterated over
+        let parameters = match name {
+            "range" => vec![("start", Some(Type::Int)), ("end", Some(Type::Int))],
+            "range_step" => vec![("start", Some(Type::Int)), ("end", Some(Type::Int)),
+                                 ("step", Some(Type::Int))],
+            "map" | "filter" | "sort_by"
+                => vec![("f", Some(Type::Function)), ("items", None)],
+            "sort" => vec![("items", None)],
+            "keys" | "values" => vec![("m", Some(Type::Map))],
+            "union" | "intersection" | "difference"
+                => vec![("a", Some(Type::Set)), ("b", Some(Type::Set))],
+            _ => {
+                self.type_of_all(arguments, scope)?;
+                return Ok(Type::Any)
+            },
+        };
+        check_arity(parameters.len(), arguments.len())?;
+
+        let mut items = Type::Any;
+        for ((parameter, expected), argument) in parameters.iter().zip(arguments) {
+            let actual = self.type_of(argument, scope)?;
+            match expected {
+                Some(expected) if !self.fits(&actual, expected) => return Err(
+                    BrainstormError::type_error(
+                        format!("Expected {} for argument '{}' of '{}', got {}",
+                                expected, parameter, name, actual))
+                        .at(&argument.span)),
+                Some(_) => {},
+                None => items = self.iterate(&actual).map_err(|e| e.at(&argument.span))?,
+            }
+        }
+
+        let returned = match name {
+            "range" | "range_step" => Type::List(Box::new(Type::Int)),
+            "filter" | "sort" | "sort_by" => Type::List(Box::new(items)),
+            "union" | "intersection" | "difference" => Type::Set,
+            _ => Type::List(Box::new(Type::Any)),
+        };
+        Ok(returned)
+    }
+
+    fn iterate(&self, iterated: &Type) -> Result<Type, BrainstormError> {
+        iterated.element().ok_or_else(|| {
+            BrainstormError::type_error(
+                format!("{} is not iterable", self.value_name(iterated)))
+        })
+    }
+
+    /// The type of applying a binary `operator`, or an error if it fails
+    /// for all values of these types.
+    fn operation(&self, operator: &Operator, lhs: &Type, rhs: &Type)
+        -> Result<Type, BrainstormError> {
+        operation_type(operator, lhs, rhs).ok_or_else(|| {
+            let (lhs, rhs) = (self.value_name(lhs), self.value_name(rhs));
+            let message = match operator {
+                Operator::LessThan | Operator::GreaterThan
+                | Operator::LessEqual | Operator::GreaterEqual
+                    => format!("Cannot compare {} and {} with '{}'", lhs, rhs, operator),
+                _ => format!("Cannot apply '{}' to {} and {}", operator, lhs, rhs),
+            };
+            BrainstormError::type_error(message)
+        })
+    }
+
+    fn expect_bool(&self, operand: &Expression, operator: &Operator, scope: &mut Scope)
+        -> Result<(), BrainstormError> {
+        match self.type_of(operand, scope)? {
+            Type::Any | Type::Bool => Ok(()),
+            actual => Err(
+                BrainstormError::type_error(
+                    format!("Operator '{}' expects Bool operands, got {}",
+                            operator, self.value_name(&actual)))
+                    .at(&operand.span)),
+        }
+    }
+
+    /// The name the runtime gives values of type `t` in its errors, so that
+    /// errors found before the program runs read the same.
+    fn value_name(&self, t: &Type) -> &'static str {
+        match t {
+            Type::Any => "Any",
+            Type::Null => "Null",
+            Type::Int => "Int",
+            Type::Float => "Float",
+            Type::Str => "String",
+            Type::Bool => "Bool",
+            Type::Char => "Char",
+            Type::List(_) => "List",
+            Type::Map => "Map",
+            Type::Set => "Set",
+            Type::Function => "Function",
+            Type::Named(name) if self.enums.contains(name) => "Enum value",
+            Type::Named(_) => "Object",
+        }
+    }
+
+    fn variable_type(&self, name: &str, scope: &Scope) -> Type {
+        if name == "this" {
+            if let Some(class) = &scope.class {
+                return Type::Named(class.clone());
+            }
+        }
+        if let Some(known) = scope.declared.get(name).or(scope.inferred.get(name)) {
+            return known.clone();
+        }
+        if self.functions.contains_key(name) || self.is_builtin(name) {
+            return Type::Function;
+        }
+        Type::Any
+    }
+
+    /// Whether `name` is a builtin the program does not replace.
+    fn is_builtin(&self, name: &str) -> bool {
+        !self.defined.contains(name)
+            && BUILTINS.iter().any(|(builtin, _)| *builtin == name)
+    }
+
+    fn is_local(&self, name: &str, scope: &Scope) -> bool {
+        scope.declared.contains_key(name) || scope.inferred.contains_key(name)
+    }
+
+    /// Whether `name` refers to an enum, as in `Shape.Circle(2)`.
+    fn is_enum(&self, name: &str, scope: &Scope) -> bool {
+        !self.is_local(name, scope) && self.enums.iter().any(|e| e == name)
+    }
+
+    fn field_type(&self, object: &Type, field: &str) -> Option<Type> {
+        let Type::Named(class) = object else { return None };
+        self.classes.get(class)?.fields.get(field).cloned()
+    }
+
+    fn type_of_all(&self, expressions: &[Expression], scope: &mut Scope)
+        -> Result<(), BrainstormError> {
+        for expression in expressions {
+            self.type_of(expression, scope)?;
+        }
+        Ok(())
+    }
+
+    /// Works out the type of `expression`, checking the operations in it.
+    fn type_of(&self, expression: &Expression, scope: &mut Scope)
+        -> Result<Type, BrainstormError> {
+        self.type_of_kind(&expression.kind, scope).map_err(|e| e.at(&expression.span))
+    }
+
+    fn type_of_kind(&self, kind: &ExpressionKind, scope: &mut Scope)
+        -> Result<Type, BrainstormError> {
+        let result = match kind {
+            ExpressionKind::Int { .. } => Type::Int,
+            ExpressionKind::String { .. } => Type::Str,
+            ExpressionKind::Boolean { .. } => Type::Bool,
+            ExpressionKind::Float { .. } => Type::Float,
+            ExpressionKind::Character { .. } => Type::Char,
+            ExpressionKind::List { items } => {
+                let mut element: Option<Type> = None;
+                for ListItem { expression, is_spread, .. } in items {
+                    let mut item = self.type_of(expression, scope)?;
+                    if *is_spread {
+                        item = self.iterate(&item).map_err(|e| e.at(&expression.span))?;
+                    }
+                    element = Some(match element {
+                        Some(element) => join(&element, &item),
+                        None => item,
+                    });
+                }
+                Type::List(Box::new(element.unwrap_or(Type::Any)))
+            },
+            ExpressionKind::Map { entries } => {
+                for (key, value) in entries {
+                    self.type_of(key, scope)?;
+                    self.type_of(value, scope)?;
+                }
+                Type::Map
+            },
+            ExpressionKind::Set { items } => {
+                self.type_of_all(items, scope)?;
+                Type::Set
+            },
+            ExpressionKind::ClassDef { params } => {
+                self.check_class(params)?;
+                Type::Named(params.name.clone())
+            },
+            ExpressionKind::Identifier { name } if self.is_enum(name, scope) => Type::Any,
+            ExpressionKind::Identifier { name } => self.variable_type(name, scope),
+            ExpressionKind::Call { function, arguments } => {
+                let name = match &function.kind {
+                    ExpressionKind::Identifier { name }
+                        if !self.is_local(name, scope) => Some(name),
+                    _ => None,
+                };
+
+                match name {
+                    Some(name) if self.functions.contains_key(name) => {
+                        let function = &self.functions[name];
+                        self.check_call(&format!("'{}'", name), &function.parameters,
+                                        arguments, scope)?;
+                        self.return_type(name, function)
+                    },
+                    Some(name) if self.is_builtin(name)
+                        => self.check_builtin(name, arguments, scope)?,
+                    _ => {
+                        let callee = self.type_of(function, scope)?;
+                        if !self.fits(&callee, &Type::Function) {
+                            return Err(
+                                BrainstormError::type_error(
+                                    format!("{} is not a function",
+                                            self.value_name(&callee)))
+                                    .at(&function.span))
+                        }
+                        self.type_of_all(arguments, scope)?;
+                        Type::Any
+                    },
+                }
+            },
+            ExpressionKind::Lambda { arguments, body } => {
+                let mut lambda_scope = scope.clone();
+                for argument in arguments {
+                    lambda_scope.forget(argument);
+                }
+                self.type_of(body, &mut lambda_scope)?;
+                Type::Function
+            },
+            ExpressionKind::Operation {
+                lhs, operator: operator @ (Operator::And | Operator::Or), rhs } => {
+                self.expect_bool(lhs, operator, scope)?;
+                self.expect_bool(rhs, operator, scope)?;
+                Type::Bool
+            },
+            ExpressionKind::Operation { lhs, operator, rhs } => {
+                let lhs = self.type_of(lhs, scope)?;
+                let rhs = self.type_of(rhs, scope)?;
+                self.operation(operator, &lhs, &rhs)?
+            },
+            ExpressionKind::Prefix { name, operator, rhs } => {
+                let rhs = self.type_of(rhs, scope)?;
+                let current = self.variable_type(name, scope);
+                let result = self.operation(operator, &current, &rhs)?;
+                self.assign(name, result.clone(), scope)?;
+                result
+            },
+            ExpressionKind::Unary { operator, operand } => {
+                match (operator, self.type_of(operand, scope)?) {
+                    (_, Type::Any) => Type::Any,
+                    (Operator::Not, Type::Bool) => Type::Bool,
+                    (Operator::Minus, operand @ (Type::Int | Type::Float)) => operand,
+                    (_, operand) => return Err(
+                        BrainstormError::type_error(
+                            format!("Cannot apply '{}' to {}",
+                                    operator, self.value_name(&operand)))),
+                }
+            },
+            ExpressionKind::Index { name, idx_exp } => {
+                let index = self.type_of(idx_exp, scope)?;
+                let element = match self.variable_type(name, scope) {
+                    Type::List(element) => *element,
+                    Type::Str => Type::Char,
+                    Type::Map | Type::Any => return Ok(Type::Any),
+                    indexed => return Err(
+                        BrainstormError::type_error(
+                            format!("Cannot index {}", self.value_name(&indexed)))),
+                };
+                if !self.fits(&index, &Type::Int) {
+                    return Err(
+                        BrainstormError::type_error("Index must be of type int")
+                            .at(&idx_exp.span))
+                }
+                element
+            },
+            ExpressionKind::Comprehension { iterate_exp, var, control_exp } => {
+                let iterated = self.type_of(control_exp, scope)?;
+                let element = self.iterate(&iterated)
+                    .map_err(|e| e.at(&control_exp.span))?;
+                let mut comprehension_scope = scope.clone();
+                comprehension_scope.declared.remove(var);
+                comprehension_scope.inferred.insert(var.clone(), element);
+                Type::List(Box::new(self.type_of(iterate_exp, &mut comprehension_scope)?))
+            },
+            ExpressionKind::FieldAccess { name, .. } if self.is_enum(name, scope)
+                => Type::Named(name.clone()),
+            ExpressionKind::FieldAccess { name, field } => {
+                let object = self.variable_type(name, scope);
+                self.field_type(&object, field).unwrap_or(Type::Any)
+            },
+            ExpressionKind::ObjectCreation { module: Some(_), arguments, .. } => {
+                self.type_of_all(arguments, scope)?;
+                Type::Any
+            },
+            ExpressionKind::ObjectCreation { module: None, class_name, arguments } => {
+                match self.classes.get(class_name) {
+                    Some(class) => {
+                        self.check_call(&format!("'{}.init'", class_name), &class.init,
+                                        arguments, scope)?;
+                        Type::Named(class_name.clone())
+                    },
+                    None => {
+                        self.type_of_all(arguments, scope)?;
+                        Type::Any
+                    },
+                }
+            },
+            ExpressionKind::MethodCall { name, arguments, .. }
+                if self.is_enum(name, scope) => {
+                self.type_of_all(arguments, scope)?;
+                Type::Named(name.clone())
+            },
+            ExpressionKind::MethodCall { name, method, arguments } => {
+                let object = self.variable_type(name, scope);
+                let known = match &object {
+                    Type::Named(class) => self.classes.get(class)
+                        .and_then(|c| c.methods.get(method)),
+                    _ => None,
+                };
+                match known {
+                    Some(signature) => {
+                        self.check_call(&format!("'{}.{}'", object, method),
+                                        &signature.parameters, arguments, scope)?;
+                        signature.returns.clone().unwrap_or(Type::Any)
+                    },
+                    None => {
+                        self.type_of_all(arguments, scope)?;
+                        Type::Any
+                    },
+                }
+            },
+        };
+        Ok(result)
+    }
+}
+
+/// The scope a function or method body starts in, where only its
+/// parameters are known.
+fn function_scope(name: &str, function: &FunctionType, class: Option<&String>) -> Scope {
+    let mut scope = Scope{
+        function: function.returns.clone().map(|returns| (name.to_string(), returns)),
+        class: class.cloned(),
+        ..Scope::default()
+    };
+    for (parameter, declared) in &function.parameters {
+        match declared {
+            // Unannotated parameters still shadow functions of the same name
+            Type::Any => scope.forget(parameter),
+            declared => {
+                scope.declared.insert(parameter.clone(), declared.clone());
+            },
+        }
+    }
+    scope
+}
+
+/// The type of applying a binary `operator` to values of types `lhs` and
+/// `rhs`: `Any` where it depends on the values, and `None` where it fails
+/// whatever they are.
+fn operation_type(operator: &Operator, lhs: &Type, rhs: &Type) -> Option<Type> {
+    let numeric = |t: &Type| matches!(t, Type::Int | Type::Float);
+    let comparison = matches!(operator, Operator::LessThan | Operator::GreaterThan
+                                        | Operator::LessEqual | Operator::GreaterEqual);
+    let membership = matches!(operator, Operator::In);
+
+    let result = match (operator, lhs, rhs) {
+        (Operator::Equal | Operator::NotEqual | Operator::And | Operator::Or
+         | Operator::Not, _, _) => Type::Bool,
+        (_, Type::Any, _) | (_, _, Type::Any)
+            if comparison || membership => Type::Bool,
+        (_, Type::Any, _) | (_, _, Type::Any) => Type::Any,
+        _ if comparison => {
+            let same_ordered = lhs == rhs
+                && matches!(lhs, Type::Str | Type::Bool | Type::Char | Type::Null);
+            if !(same_ordered || numeric(lhs) && numeric(rhs)) {
+                return None
+            }
+            Type::Bool
+        },
+        (Operator::In, _, Type::List(_) | Type::Map | Type::Set)
+        | (Operator::In, Type::Str | Type::Char, Type::Str) => Type::Bool,
+        (Operator::In, _, _) => return None,
+        (Operator::Power, Type::Int, Type::Int) => Type::Any,
+        (_, Type::Int, Type::Int) => Type::Int,
+        (_, lhs, rhs) if numeric(lhs) && numeric(rhs) => Type::Float,
+        (Operator::Plus, Type::Str, Type::Str | Type::Char)
+        | (Operator::Plus, Type::Char, Type::Str)
+        | (Operator::Times, Type::Str, Type::Int)
+        | (Operator::Times, Type::Int, Type::Str) => Type::Str,
+        (Operator::Plus, Type::List(lhs), Type::List(rhs))
+            => Type::List(Box::new(join(lhs, rhs))),
+        (Operator::Times, list @ Type::List(_), Type::Int)
+        | (Operator::Times, Type::Int, list @ Type::List(_)) => list.clone(),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Whether running `statements` always ends in a `return` or a `throw`
+/// rather than by reaching their end.
+fn always_returns(statements: &[Statement]) -> bool {
+    match statements.last().map(|statement| &statement.kind) {
+        Some(StatementKind::Return { .. } | StatementKind::Throw { .. }) => true,
+        Some(StatementKind::If { params }) => {
+            always_returns(&params.statements)
+                && params.elif_data.1.iter().all(|statements| always_returns(statements))
+                && params.else_statements.as_deref().is_some_and(always_returns)
+        },
+        _ => false,
+    }
+}
+
+/// The variables matching `pattern` binds.
+fn pattern_bindings(pattern: &Pattern) -> Vec<String> {
+    match &pattern.kind {
+        PatternKind::Wildcard | PatternKind::Literal { .. } => vec![],
+        PatternKind::Binding { name } => vec![name.clone()],
+        PatternKind::List { items, rest } => {
+            let mut names: Vec<String> = items.iter()
+                .flat_map(pattern_bindings).collect();
+            names.extend(rest.clone());
+            names
+        },
+        PatternKind::Object { fields, .. }
+            => fields.iter().flat_map(|(_, pattern)| pattern_bindings(pattern)).collect(),
+        PatternKind::Variant { items, .. }
+            => items.iter().flat_map(pattern_bindings).collect(),
+    }
+}
+
+type FunctionDefinition<'a> = (&'a str, &'a [String], &'a Signature, &'a [Statement]);
+
+/// Finds the definitions in `statements`, including those nested in other
+/// statements, functions and classes.
+fn collect_definitions<'a>(statements: &'a [Statement],
+                           functions: &mut Vec<FunctionDefinition<'a>>,
+                           classes: &mut Vec<&'a UserClass>, enums: &mut Vec<String>) {
+    for statement in statements {
+        match &statement.kind {
+            StatementKind::FunctionDefinition { name, arguments,
+                                                signature, statements } => {
+                functions.push((name, arguments, signature, statements));
+                collect_definitions(statements, functions, classes, enums);
+            },
+            StatementKind::EnumDefinition { name, .. } => enums.push(name.clone()),
+            StatementKind::Public { statement } => collect_definitions(
+                std::slice::from_ref(statement), functions, classes, enums),
+            StatementKind::Expression { expression: Expression {
+                kind: ExpressionKind::ClassDef { params }, .. } } => {
+                classes.push(params);
+                if let Some(statements) = &params.init.statements {
+                    collect_definitions(statements, functions, classes, enums);
+                }
+                for method in params.methods.values() {
+                    collect_definitions(&method.statements, functions, classes, enums);
+                }
+            },
+            StatementKind::If { params } => {
+                collect_definitions(&params.statements, functions, classes, enums);
+                for statements in &params.elif_data.1 {
+                    collect_definitions(statements, functions, classes, enums);
+                }
+                if let Some(statements) = &params.else_statements {
+                    collect_definitions(statements, functions, classes, enums);
+                }
+            },
+            StatementKind::While { statements, .. }
+                => collect_definitions(statements, functions, classes, enums),
+            StatementKind::For { params }
+                => collect_definitions(&params.statements, functions, classes, enums),
+            StatementKind::Match { arms, .. } => {
+                for arm in arms {
+                    collect_definitions(&arm.statements, functions, classes, enums);
+                }
+            },
+            StatementKind::Try { statements, catch, finally } => {
+                collect_definitions(statements, functions, classes, enums);
+                if let Some(catch) = catch {
+                    collect_definitions(&catch.statements, functions, classes, enums);
+                }
+                if let Some(finally) = finally {
+                    collect_definitions(finally, functions, classes, enums);
+                }
+            },
+            _ => {},
+        }
+    }
+}
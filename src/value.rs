@@ -1,9 +1,19 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign };
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
-use crate::ast::{Expression, Statement, ClassInitDef, ClassField, ClassMethod};
+use crate::ast::{Expression, Statement, ClassInitDef, ClassField, ClassMethod, 
+                 EnumVariant};
+use crate::collections::{ValueMap, ValueSet};
+use crate::error::BrainstormError;
+use crate::eval::Interpreter;
+
+/// A function implemented natively. It gets the interpreter so that it can
+/// call functions it was passed.
+pub type Builtin = fn(&mut Interpreter, Vec<Value>) -> Result<Value, BrainstormError>;
 
 #[derive(Debug)]
 pub enum Value {
@@ -14,12 +24,146 @@ pub enum Value {
     Float{f: f64},
     Char{c: char},
     List{e: Vec<Value>},
-    Function{name: String, f: fn(Vec<Value>) -> Result<Value, String>},
+    Map{m: ValueMap},
+    Set{e: ValueSet},
+    Function{name: String, f: Builtin},
+    // Functions imported from a module run in the module's scope rather
+    // than the caller's
     UserDefFunction{name: String, statements: Vec<Statement>, 
-        arguments: Vec<String>, return_expression: Option<Expression>},
+        arguments: Vec<String>, module: Option<Rc<HashMap<String, Value>>>},
+    Lambda{arguments: Vec<String>, body: Box<Expression>, 
+           captured: Rc<HashMap<String, Value>>},
     Object{name: String, fields: HashMap<String, ClassField>, 
            init: ClassInitDef, methods: HashMap<String, ClassMethod>,
-           parent_class: Option<String>}
+           parent_class: Option<String>},
+    EnumType{name: String, variants: Vec<EnumVariant>},
+    Enum{name: String, tag: String, payload: Vec<(String, Value)>},
+    Error{error: Box<BrainstormError>},
+    Module{path: String, members: Rc<HashMap<String, Value>>},
+}
+
+impl Value {
+    /// Name of the value's type, as used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "Null",
+            Value::Int { .. } => "Int",
+            Value::Str { .. } => "String",
+            Value::Bool { .. } => "Bool",
+            Value::Float { .. } => "Float",
+            Value::Char { .. } => "Char",
+            Value::List { .. } => "List",
+            Value::Map { .. } => "Map",
+            Value::Set { .. } => "Set",
+            Value::Function { .. } => "Function",
+            Value::UserDefFunction { .. } => "Function",
+            Value::Lambda { .. } => "Function",
+            Value::Object { .. } => "Object",
+            Value::EnumType { .. } => "Enum",
+            Value::Enum { .. } => "Enum value",
+            Value::Error { .. } => "Error",
+            Value::Module { .. } => "Module",
+        }
+    }
+
+    /// The values a `for` loop over `self` visits: the items of a list or
+    /// set, the characters of a string or the keys of a map.
+    pub fn items(&self) -> Result<Vec<Value>, BrainstormError> {
+        match self {
+            Value::List { e } => Ok(e.clone()),
+            Value::Str { s } => Ok(s.chars().map(|c| Value::Char{c}).collect()),
+            Value::Map { m } => Ok(m.keys().cloned().collect()),
+            Value::Set { e } => Ok(e.iter().cloned().collect()),
+            v => Err(BrainstormError::type_error(
+                format!("{} is not iterable", v.type_name())))
+        }
+    }
+
+    /// Whether the value can be a map key. Only values compared by their
+    /// contents are.
+    pub fn is_hashable(&self) -> bool {
+        match self {
+            Value::Null 
+            | Value::Int { .. } 
+            | Value::Str { .. } 
+            | Value::Bool { .. } 
+            | Value::Float { .. } 
+            | Value::Char { .. } => true,
+            Value::List { e } => e.iter().all(Value::is_hashable),
+            Value::Enum { payload, .. } 
+                => payload.iter().all(|(_, value)| value.is_hashable()),
+            _ => false
+        }
+    }
+
+    /// Raises `self` to the power of `rhs`. Integers stay integers unless the
+    /// exponent is negative.
+    pub fn pow(&self, rhs: &Value) -> Result<Value, BrainstormError> {
+        match (self, rhs) {
+            (Value::Int { v: lv }, Value::Int { v: rv }) => {
+                match u32::try_from(*rv) {
+                    Ok(exp) => checked_int(lv.checked_pow(exp), "**"),
+                    Err(_) => Ok(Value::Float{f: f64::from(*lv).powi(*rv)}),
+                }
+            },
+            (Value::Float { f: lf }, Value::Float { f: rf }) 
+                => Ok(Value::Float{f: lf.powf(*rf)}),
+            (Value::Int { v }, Value::Float { f }) 
+                => Ok(Value::Float{f: f64::from(*v).powf(*f)}),
+            (Value::Float { f }, Value::Int { v }) 
+                => Ok(Value::Float{f: f.powi(*v)}),
+            _ => Err(operand_error("**", self, rhs))
+        }
+    }
+
+    /// Divides `self` by `rhs`, rounding the quotient towards negative 
+    /// infinity.
+    pub fn floor_div(&self, rhs: &Value) -> Result<Value, BrainstormError> {
+        match (self, rhs) {
+            (Value::Int { v: lv }, Value::Int { v: rv }) => {
+                let Value::Int { v: quotient } 
+                    = checked_div(lv.checked_div(*rv), *rv, "~/")? 
+                    else { unreachable!() };
+                if lv % rv != 0 && (*lv < 0) != (*rv < 0) {
+                    return checked_int(quotient.checked_sub(1), "~/")
+                }
+                Ok(Value::Int{v: quotient})
+            },
+            (Value::Float { f: lf }, Value::Float { f: rf }) 
+                => Ok(Value::Float{f: (lf / rf).floor()}),
+            (Value::Int { v }, Value::Float { f }) 
+                => Ok(Value::Float{f: (f64::from(*v) / f).floor()}),
+            (Value::Float { f }, Value::Int { v }) 
+                => Ok(Value::Float{f: (f / f64::from(*v)).floor()}),
+            _ => Err(operand_error("~/", self, rhs))
+        }
+    }
+
+    /// Whether `item` occurs in `self`, as tested by the `in` operator.
+    /// Strings contain their substrings and characters, and maps their keys.
+    pub fn contains(&self, item: &Value) -> Result<bool, BrainstormError> {
+        match (self, item) {
+            (Value::List { e }, _) => Ok(e.contains(item)),
+            (Value::Map { m }, _) => Ok(m.contains_key(item)),
+            (Value::Set { e }, _) => Ok(e.contains(item)),
+            (Value::Str { s }, Value::Str { s: sub }) => Ok(s.contains(sub.as_str())),
+            (Value::Str { s }, Value::Char { c }) => Ok(s.contains(*c)),
+            _ => Err(operand_error("in", item, self))
+        }
+    }
+
+    /// Orders two values, or returns `None` if their types cannot be compared.
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Int { .. } | Value::Float { .. }, 
+             Value::Int { .. } | Value::Float { .. })
+            | (Value::Str { .. }, Value::Str { .. })
+            | (Value::Bool { .. }, Value::Bool { .. })
+            | (Value::Char { .. }, Value::Char { .. })
+            | (Value::Null, Value::Null) => Some(self.cmp(other)),
+            _ => None
+        }
+    }
 }
 
 impl fmt::Display for Value {
@@ -49,12 +193,40 @@ impl fmt::Display for Value {
                 }
                 to_print.push(']');
             },
+            Value::Map { m } => {
+                let entries: Vec<String> = m.iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect();
+                to_print = format!("{{{}}}", entries.join(", "));
+            },
+            // `{}` is an empty map, so empty sets print as the call making one
+            Value::Set { e } if e.is_empty() => to_print = String::from("set()"),
+            Value::Set { e } => {
+                let items: Vec<String> = e.iter().map(Value::to_string).collect();
+                to_print = format!("{{{}}}", items.join(", "));
+            },
             Value::Function { name, .. } 
                 => to_print = format!("Function \"{}\"", name),
             Value::UserDefFunction { name, .. } 
                 => to_print = format!("Function \"{}\"", name),
+            Value::Lambda { arguments, .. } 
+                => to_print = format!("Lambda \"fn({})\"", arguments.join(", ")),
             Value::Object { name,.. } 
-                => to_print = format!("Class \"{}\"", name)
+                => to_print = format!("Class \"{}\"", name),
+            Value::EnumType { name, .. } 
+                => to_print = format!("Enum \"{}\"", name),
+            Value::Enum { name, tag, payload } if payload.is_empty() 
+                => to_print = format!("{}.{}", name, tag),
+            Value::Enum { name, tag, payload } => {
+                let values: Vec<String> = payload.iter()
+                    .map(|(_, value)| value.to_string())
+                    .collect();
+                to_print = format!("{}.{}({})", name, tag, values.join(", "));
+            },
+            Value::Error { error } 
+                => to_print = format!("{} error: {}", error.kind(), error.message()),
+            Value::Module { path, .. } 
+                => to_print = format!("Module \"{}\"", path),
         };
         write!(f, "{}", to_print)
     }
@@ -142,6 +314,9 @@ impl PartialEq for Value {
             (Value::Char { c: l_c }, Value::Char { c: r_c }) 
                 => l_c == r_c,
             (Value::List { e: l_e }, Value::List { e: r_e }) => {
+                if l_e.len() != r_e.len() {
+                    return false
+                }
                 for (lhs, rhs) in l_e.iter().zip(r_e) {
                     if lhs != rhs {
                         return false
@@ -153,6 +328,15 @@ impl PartialEq for Value {
                 => f64::from(*v) == *f,
             (Value::Int { v }, Value::Float { f })
                 => f64::from(*v) == *f,
+            (Value::Map { m: l_m }, Value::Map { m: r_m }) 
+                => l_m == r_m,
+            (Value::Set { e: l_e }, Value::Set { e: r_e }) 
+                => l_e == r_e,
+            (Value::Enum { name: l_n, tag: l_t, payload: l_p }, 
+             Value::Enum { name: r_n, tag: r_t, payload: r_p }) 
+                => l_n == r_n && l_t == r_t && l_p == r_p,
+            (Value::Error { error: l_e }, Value::Error { error: r_e }) 
+                => l_e.kind() == r_e.kind() && l_e.message() == r_e.message(),
             _ 
                 => core::mem::discriminant(self) 
                     == core::mem::discriminant(other),
@@ -223,6 +407,35 @@ impl Eq for Value {
     
 }
 
+// Equal values must hash alike, so integral floats hash like the Int they
+// equal.
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Value::Int { v } => i64::from(*v).hash(state),
+            #[allow(clippy::cast_possible_truncation)]
+            Value::Float { f } if f.fract() == 0.0 
+                                  && f.abs() < 9_007_199_254_740_992.0 
+                => (*f as i64).hash(state),
+            Value::Float { f } => f.to_bits().hash(state),
+            Value::Str { s } => s.hash(state),
+            Value::Bool { b } => b.hash(state),
+            Value::Char { c } => c.hash(state),
+            Value::List { e } => e.hash(state),
+            // Maps and sets compare regardless of order, and other values
+            // by type
+            Value::Map { m } => m.len().hash(state),
+            Value::Set { e } => e.len().hash(state),
+            Value::Enum { name, tag, payload } => {
+                name.hash(state);
+                tag.hash(state);
+                payload.hash(state);
+            },
+            _ => core::mem::discriminant(self).hash(state),
+        }
+    }
+}
+
 impl Clone for Value {
     fn clone(&self) -> Self {
         match self {
@@ -240,229 +453,248 @@ impl Clone for Value {
                 => Self::Char { c: *c },
             Self::List { e } 
                 => Self::List { e: e.clone() },
+            Self::Map { m } 
+                => Self::Map { m: m.clone() },
+            Self::Set { e } 
+                => Self::Set { e: e.clone() },
             Self::Function { name, f } 
                 => Self::Function { name: name.clone(), f: *f },
-            Self::UserDefFunction { name, statements, 
-                                    arguments, return_expression } 
+            Self::UserDefFunction { name, statements, arguments, module } 
                 => Self::UserDefFunction { 
                     name: name.clone(), 
                     statements: statements.clone(), 
+                    arguments: arguments.clone(),
+                    module: module.clone() },
+            Self::Lambda { arguments, body, captured } 
+                => Self::Lambda { 
                     arguments: arguments.clone(), 
-                    return_expression: return_expression.clone() },
+                    body: body.clone(), 
+                    captured: captured.clone() },
             Self::Object { name, fields, init, methods, parent_class } 
                 => Self::Object{name: name.clone(), 
                                   fields: fields.clone(), 
                                   init: init.clone(), 
                                   methods: methods.clone(),
-                                  parent_class: parent_class.clone()}
+                                  parent_class: parent_class.clone()},
+            Self::EnumType { name, variants } 
+                => Self::EnumType { name: name.clone(), variants: variants.clone() },
+            Self::Enum { name, tag, payload } 
+                => Self::Enum { name: name.clone(), 
+                                tag: tag.clone(), 
+                                payload: payload.clone() },
+            Self::Error { error } => Self::Error { error: error.clone() },
+            Self::Module { path, members } 
+                => Self::Module { path: path.clone(), members: members.clone() },
         }
     }
 }
 
 impl Add for Value {
-    type Output = Value;
+    type Output = Result<Value, BrainstormError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl Add for &Value {
+    type Output = Result<Value, BrainstormError>;
 
     fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv + rv},
+                => checked_int(lv.checked_add(*rv), "+"),
             (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf + rf},
+                => Ok(Value::Float{f: lf + rf}),
             (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(v) + f},
+                => Ok(Value::Float{f: f64::from(*v) + f}),
             (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f + f64::from(v)},
-            _ => Value::Null
+                => Ok(Value::Float{f: f + f64::from(*v)}),
+            (Value::Str { s: ls }, Value::Str { s: rs }) 
+                => Ok(Value::Str{s: format!("{ls}{rs}")}),
+            (Value::Str { s }, Value::Char { c }) 
+                => Ok(Value::Str{s: format!("{s}{c}")}),
+            (Value::Char { c }, Value::Str { s }) 
+                => Ok(Value::Str{s: format!("{c}{s}")}),
+            (Value::List { e: le }, Value::List { e: re }) 
+                => Ok(Value::List{e: [le.as_slice(), re.as_slice()].concat()}),
+            _ => Err(operand_error("+", self, rhs))
         }
     }
 }
 
-impl AddAssign for Value {
-    fn add_assign(&mut self, other: Self) {
-        *self = match (&self, other) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: *lv + rv},
-            (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: *lf + rf},
-            (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) + f},
-            (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: *f + f64::from(v)},
-            _ => Value::Null
-        };
+impl Sub for Value {
+    type Output = Result<Value, BrainstormError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
     }
 }
 
-impl Sub for Value {
-    type Output = Value;
+impl Sub for &Value {
+    type Output = Result<Value, BrainstormError>;
 
     fn sub(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv - rv},
+                => checked_int(lv.checked_sub(*rv), "-"),
             (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf - rf},
+                => Ok(Value::Float{f: lf - rf}),
             (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(v) - f},
+                => Ok(Value::Float{f: f64::from(*v) - f}),
             (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f - f64::from(v)},
-            _ => Value::Null
+                => Ok(Value::Float{f: f - f64::from(*v)}),
+            _ => Err(operand_error("-", self, rhs))
         }
     }
 }
 
-impl SubAssign for Value {
-    fn sub_assign(&mut self, other: Self) {
-        *self = match (&self, other) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: *lv - rv},
-            (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: *lf - rf},
-            (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) - f},
-            (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: *f - f64::from(v)},
-            _ => Value::Null
-        };
+impl Mul for Value {
+    type Output = Result<Value, BrainstormError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
     }
 }
 
-impl Div for Value {
-    type Output = Value;
+impl Mul for &Value {
+    type Output = Result<Value, BrainstormError>;
 
-    fn div(self, rhs: Self) -> Self::Output {
+    fn mul(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv / rv},
+                => checked_int(lv.checked_mul(*rv), "*"),
             (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf / rf},
+                => Ok(Value::Float{f: lf * rf}),
             (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(v) / f},
+                => Ok(Value::Float{f: f64::from(*v) * f}),
             (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f / f64::from(v)},
-            _ => Value::Null
+                => Ok(Value::Float{f: f * f64::from(*v)}),
+            // Repeating a sequence a negative number of times empties it
+            (Value::Str { s }, Value::Int { v: n }) 
+            | (Value::Int { v: n }, Value::Str { s }) 
+                => Ok(Value::Str{s: s.repeat(usize::try_from(*n).unwrap_or(0))}),
+            (Value::List { e }, Value::Int { v: n }) 
+            | (Value::Int { v: n }, Value::List { e }) 
+                => {
+                let len = e.len() * usize::try_from(*n).unwrap_or(0);
+                Ok(Value::List{e: e.iter().cycle().take(len).cloned().collect()})
+            },
+            _ => Err(operand_error("*", self, rhs))
         }
     }
 }
 
-impl DivAssign for Value {
-    fn div_assign(&mut self, other: Self) {
-        *self = match (&self, other) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: *lv / rv},
-            (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: *lf / rf},
-            (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) / f},
-            (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: *f / f64::from(v)},
-            _ => Value::Null
-        };
+impl Div for Value {
+    type Output = Result<Value, BrainstormError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        &self / &rhs
     }
 }
 
-impl Mul for Value {
-    type Output = Value;
+impl Div for &Value {
+    type Output = Result<Value, BrainstormError>;
 
-    fn mul(self, rhs: Self) -> Self::Output {
+    fn div(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv * rv},
+                => checked_div(lv.checked_div(*rv), *rv, "/"),
             (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf * rf},
+                => Ok(Value::Float{f: lf / rf}),
             (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(v) * f},
+                => Ok(Value::Float{f: f64::from(*v) / f}),
             (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f * f64::from(v)},
-            _ => Value::Null
+                => Ok(Value::Float{f: f / f64::from(*v)}),
+            _ => Err(operand_error("/", self, rhs))
         }
     }
 }
 
-impl MulAssign for Value {
-    fn mul_assign(&mut self, other: Self) {
-        *self = match (&self, other) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: *lv + rv},
-            (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: *lf + rf},
-            (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) * f},
-            (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: *f * f64::from(v)},
-            _ => Value::Null
-        };
+impl Rem for Value {
+    type Output = Result<Value, BrainstormError>;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        &self % &rhs
     }
 }
 
-impl Add for &Value {
-    type Output = Value;
+impl Rem for &Value {
+    type Output = Result<Value, BrainstormError>;
 
-    fn add(self, rhs: Self) -> Self::Output {
+    fn rem(self, rhs: Self) -> Self::Output {
+        // The remainder takes the sign of the divisor, to go with `~/`
+        // rounding towards negative infinity
         match (self, rhs) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv + rv},
+            (Value::Int { v: lv }, Value::Int { v: rv }) => {
+                let Value::Int { v: remainder } 
+                    = checked_div(lv.checked_rem(*rv), *rv, "%")? 
+                    else { unreachable!() };
+                if remainder != 0 && (remainder < 0) != (*rv < 0) {
+                    return Ok(Value::Int{v: remainder + rv})
+                }
+                Ok(Value::Int{v: remainder})
+            },
             (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf + rf},
+                => Ok(Value::Float{f: floored_rem(*lf, *rf)}),
             (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) + f},
+                => Ok(Value::Float{f: floored_rem(f64::from(*v), *f)}),
             (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f + f64::from(*v)},
-            _ => Value::Null
+                => Ok(Value::Float{f: floored_rem(*f, f64::from(*v))}),
+            _ => Err(operand_error("%", self, rhs))
         }
     }
 }
 
-impl Sub for &Value {
-    type Output = Value;
+impl Neg for Value {
+    type Output = Result<Value, BrainstormError>;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv - rv},
-            (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf - rf},
-            (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) - f},
-            (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f - f64::from(*v)},
-            _ => Value::Null
-        }
+    fn neg(self) -> Self::Output {
+        -&self
     }
 }
 
-impl Div for &Value {
-    type Output = Value;
+impl Neg for &Value {
+    type Output = Result<Value, BrainstormError>;
 
-    fn div(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv / rv},
-            (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf / rf},
-            (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) / f},
-            (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f / f64::from(*v)},
-            _ => Value::Null
+    fn neg(self) -> Self::Output {
+        match self {
+            Value::Int { v } => checked_int(v.checked_neg(), "-"),
+            Value::Float { f } => Ok(Value::Float{f: -f}),
+            _ => Err(BrainstormError::type_error(
+                format!("Cannot apply '-' to {}", self.type_name())))
         }
     }
 }
 
-impl Mul for &Value {
-    type Output = Value;
+fn operand_error(operator: &str, lhs: &Value, rhs: &Value) -> BrainstormError {
+    BrainstormError::type_error(format!("Cannot apply '{}' to {} and {}", 
+                                        operator, 
+                                        lhs.type_name(), 
+                                        rhs.type_name()))
+}
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Int { v: lv }, Value::Int { v: rv }) 
-                => Value::Int{v: lv * rv},
-            (Value::Float { f: lf }, Value::Float { f: rf }) 
-                => Value::Float{f: lf * rf},
-            (Value::Int { v }, Value::Float { f }) 
-                => Value::Float{f: f64::from(*v) * f},
-            (Value::Float { f }, Value::Int { v }) 
-                => Value::Float{f: f * f64::from(*v)},
-            _ => Value::Null
-        }
+fn checked_int(result: Option<i32>, operator: &str) 
+    -> Result<Value, BrainstormError> {
+    match result {
+        Some(v) => Ok(Value::Int{v}),
+        None => Err(BrainstormError::arithmetic_error(
+            format!("Integer overflow in '{}'", operator)))
+    }
+}
+
+fn floored_rem(dividend: f64, divisor: f64) -> f64 {
+    let remainder = dividend % divisor;
+    if remainder != 0.0 && (remainder < 0.0) != (divisor < 0.0) {
+        return remainder + divisor
+    }
+    remainder
+}
+
+fn checked_div(result: Option<i32>, divisor: i32, operator: &str) 
+    -> Result<Value, BrainstormError> {
+    if divisor == 0 {
+        return Err(BrainstormError::arithmetic_error("Division by zero"))
     }
+    checked_int(result, operator)
 }
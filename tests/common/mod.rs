@@ -3,10 +3,39 @@ use std::path::PathBuf;
 use std::str;
 
 pub fn get_program_output(file: &str) -> (String, String){
+    let (log, errors, _) = get_program_result(file);
+
+    (log, errors)
+}
+
+pub fn get_program_result(file: &str) -> (String, String, Option<i32>){
+    let mut command = interpreter();
+    command.arg(file);
+
+    collect_output(command)
+}
+
+/// Runs the interpreter with `args`, with the import search path variables
+/// set to `vars` only.
+pub fn get_program_result_with(args: &[&str], vars: &[(&str, &str)]) 
+    -> (String, String, Option<i32>){
+    let mut command = interpreter();
+    command.args(args)
+        .env_remove("BRNSTM_LIB")
+        .env_remove("BRNSTM_PATH")
+        .envs(vars.iter().copied());
+
+    collect_output(command)
+}
+
+fn interpreter() -> Command {
     let mut path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
     path.push("target/debug/brainstorm");
-    let output = Command::new(path)
-        .arg(file)
+    Command::new(path)
+}
+
+fn collect_output(mut command: Command) -> (String, String, Option<i32>){
+    let output = command
         .output()
         .expect("Failed to execute command");
 
@@ -22,7 +51,7 @@ pub fn get_program_output(file: &str) -> (String, String){
         Err(..) => panic!("got non UTF-8 data from stderr"),
     });
 
-    (log, errors)
+    (log, errors, output.status.code())
 }
 
 pub fn read_file(path: &str) -> String {
@@ -216,13 +216,14 @@ mod tests {
 
     #[test]
     fn test_object_visibility(){
-        let(log, errors)
-            = common::get_program_output("tests/test_sources/test_object_visibility.txt");
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_object_visibility.txt");
         
         let expected_output
             = common::read_file("tests/test_output/test_object_visibility.output");
         
-        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(3), status);
     }
 
     #[test]
@@ -235,4 +236,591 @@ mod tests {
         
         assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
     }
+
+    #[test]
+    fn test_syntax_error(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_syntax_error.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_syntax_error.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(2), status);
+    }
+
+    #[test]
+    fn test_precedence(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_precedence.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_precedence.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_logic(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_logic.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_logic.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_logic_type_error(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_logic_type_error.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_logic_type_error.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(4), status);
+    }
+
+    #[test]
+    fn test_operators_extended(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_operators_extended.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_operators_extended.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_operand_type_error(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_operand_type_error.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_operand_type_error.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(4), status);
+    }
+
+    #[test]
+    fn test_division_by_zero(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_division_by_zero.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_division_by_zero.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(10), status);
+    }
+
+    #[test]
+    fn test_mixed_arithmetic(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_mixed_arithmetic.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_mixed_arithmetic.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_sequence_operators(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_sequence_operators.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_sequence_operators.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_early_return(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_early_return.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_early_return.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_break_continue(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_break_continue.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_break_continue.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_break_outside_loop(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_break_outside_loop.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_break_outside_loop.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(2), status);
+    }
+
+    #[test]
+    fn test_lambda(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_lambda.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_lambda.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_call_expressions(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_call_expressions.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_call_expressions.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_higher_order_builtins(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_higher_order_builtins.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_higher_order_builtins.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_maps(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_maps.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_maps.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_sets(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_sets.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_sets.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_enums(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_enums.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_enums.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_match(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_match.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_match.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_match_no_arm(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_match_no_arm.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_match_no_arm.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(11), status);
+    }
+
+    #[test]
+    fn test_exceptions(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_exceptions.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_exceptions.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_uncaught_throw(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_uncaught_throw.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_uncaught_throw.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(9), status);
+    }
+
+    #[test]
+    fn test_type_annotations(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_type_annotations.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_type_annotations.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_type_mismatch(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_type_mismatch.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_type_mismatch.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(4), status);
+    }
+
+    #[test]
+    fn test_type_inference(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_type_inference.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_type_inference.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_inferred_type_error(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_inferred_type_error.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_inferred_type_error.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(4), status);
+    }
+
+    #[test]
+    fn test_import_module(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_import_module.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_module.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_import_missing_name(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_import_missing_name.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_missing_name.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(7), status);
+    }
+
+    #[test]
+    fn test_import_nested(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_import_nested.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_nested.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_import_chain(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_import_chain.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_chain.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(7), status);
+    }
+
+    #[test]
+    fn test_import_search_path(){
+        let(log, errors, _)
+            = common::get_program_result_with(
+                &["-I", "test_lib", "tests/test_sources/test_import_search_path.txt"], 
+                &[("BRNSTM_PATH", "/nonexistent:test_lib/nested")]);
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_search_path.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_import_not_found(){
+        let(log, errors, status)
+            = common::get_program_result_with(
+                &["-Itest_lib", "tests/test_sources/test_import_not_found.txt"], 
+                &[("BRNSTM_PATH", "test_lib/nested")]);
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_not_found.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(7), status);
+    }
+
+    #[test]
+    fn test_import_twice(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_import_twice.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_twice.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_import_cycle(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_import_cycle.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_cycle.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(7), status);
+    }
+
+    #[test]
+    fn test_import_pub(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_import_pub.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_pub.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_pub_nested(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_pub_nested.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_pub_nested.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(2), status);
+    }
+
+    #[test]
+    fn test_error_snippet(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_error_snippet.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_error_snippet.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(5), status);
+    }
+
+    #[test]
+    fn test_int_literal_overflow(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_int_literal_overflow.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_int_literal_overflow.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(2), status);
+    }
+
+    #[test]
+    fn test_type_redefined_function(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_type_redefined_function.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_type_redefined_function.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_field_type_mismatch(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_field_type_mismatch.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_field_type_mismatch.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(4), status);
+    }
+
+    #[test]
+    fn test_type_branch_function(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_type_branch_function.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_type_branch_function.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_type_nested_function(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_type_nested_function.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_type_nested_function.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_import_collision(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_import_collision.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_collision.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(3), status);
+    }
+
+    #[test]
+    fn test_index_assign_error(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_index_assign_error.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_index_assign_error.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(5), status);
+    }
+
+    #[test]
+    fn test_sort_mixed_types(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_sort_mixed_types.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_sort_mixed_types.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(4), status);
+    }
+
+    #[test]
+    fn test_sort_nan(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_sort_nan.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_sort_nan.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(4), status);
+    }
+
+    #[test]
+    fn test_shadow_builtins(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_shadow_builtins.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_shadow_builtins.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_class_expression(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_class_expression.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_class_expression.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_class_redefined(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_class_redefined.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_class_redefined.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_import_scopes(){
+        let(log, errors)
+            = common::get_program_output("tests/test_sources/test_import_scopes.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_import_scopes.output");
+        
+        assert_eq!(expected_output, log, "\nErrors:\n{}\n", errors);
+    }
+
+    #[test]
+    fn test_arity_checked(){
+        let(log, errors, status)
+            = common::get_program_result("tests/test_sources/test_arity_checked.txt");
+        
+        let expected_output
+            = common::read_file("tests/test_output/test_arity_checked.output");
+        
+        assert_eq!(expected_output, errors, "\nOutput:\n{}\n", log);
+        assert_eq!(Some(6), status);
+    }
 }
\ No newline at end of file